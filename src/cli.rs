@@ -1,8 +1,13 @@
+mod build;
+mod compose;
 mod container;
+mod image;
 mod install;
 mod k8s;
 mod kube;
 mod network;
+mod pod;
+mod reverse;
 pub mod service;
 pub mod unit;
 pub mod volume;
@@ -17,28 +22,37 @@ use std::{
     ffi::OsStr,
     fmt::{self, Display},
     fs,
-    io::{self, Write},
+    io::{self, IsTerminal, Read, Write},
     iter, mem,
     path::{Path, PathBuf},
+    process::{Command, ExitStatus},
     rc::Rc,
 };
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use color_eyre::{
     eyre::{self, Context},
     Help,
 };
 use docker_compose_types::{Compose, MapOrEmpty};
 use k8s_openapi::api::core::v1::{PersistentVolumeClaim, Pod};
+use serde::Serialize;
 
 use crate::quadlet;
 
 use self::{
-    container::Container, install::Install, kube::Kube, network::Network, service::Service,
-    unit::Unit, volume::Volume,
+    build::Build,
+    container::Container,
+    image::{Image, Pull},
+    install::Install,
+    kube::Kube,
+    network::Network,
+    service::Service,
+    unit::Unit,
+    volume::Volume,
 };
 
-#[allow(clippy::option_option)]
+#[allow(clippy::option_option, clippy::struct_excessive_bools)]
 #[derive(Parser, Debug, Clone, PartialEq)]
 #[command(author, version, about, subcommand_precedence_over_arg = true)]
 pub struct Cli {
@@ -48,10 +62,12 @@ pub struct Cli {
     /// if no path is provided the file will be placed in the current working directory.
     ///
     /// If not provided, the name of the generated file will be taken from,
-    /// the `name` parameter for volumes and networks,
+    /// the `name` parameter for volumes, networks, and pods,
     /// the filename of the kube file,
     /// the container name,
-    /// or the name of the container image.
+    /// the name of the container image,
+    /// the name of the image tag of a build,
+    /// or the name of the pulled image.
     #[arg(short, long, group = "file_out")]
     file: Option<Option<PathBuf>>,
 
@@ -60,7 +76,9 @@ pub struct Cli {
     /// Conflicts with the --file option
     ///
     /// Equivalent to `--file $XDG_CONFIG_HOME/containers/systemd/` for non-root users,
-    /// or `--file /etc/containers/systemd/` for root.
+    /// or `--file /etc/containers/systemd/` for root or with the --system option.
+    ///
+    /// The directory is created if it does not exist.
     ///
     /// The name of the file can be specified with the --name option.
     #[arg(
@@ -72,6 +90,10 @@ pub struct Cli {
     )]
     unit_directory: bool,
 
+    /// Use the system unit directory, `/etc/containers/systemd/`, even for non-root users
+    #[arg(long, requires = "unit_directory")]
+    system: bool,
+
     /// Override the name of the generated file (without the extension)
     ///
     /// This only applies if a file was not given to the --file option,
@@ -88,6 +110,12 @@ pub struct Cli {
     #[arg(long, alias = "override", requires = "file_out")]
     overwrite: bool,
 
+    /// Run `systemctl daemon-reload` after generating files
+    ///
+    /// Uses `systemctl --user` unless running as root or the --system option is used.
+    #[arg(long, requires = "file_out")]
+    daemon_reload: bool,
+
     /// Skip the check for existing services of the same name
     ///
     /// By default, podlet will check for existing services with the same name as
@@ -97,6 +125,21 @@ pub struct Cli {
     #[arg(long, requires = "file_out")]
     skip_services_check: bool,
 
+    /// Add a description to units without one, based on the name of the generated file
+    ///
+    /// E.g. `podlet --auto-description podman run quay.io/podman/hello`
+    /// adds "Description=Podman container for hello".
+    /// For compose files, the service's name is used.
+    #[arg(long)]
+    auto_description: bool,
+
+    /// The format to print the generated files in
+    ///
+    /// `json` prints the files as a JSON array, for use by other tools.
+    /// Only `quadlet` can be used with the --file and --unit-directory options.
+    #[arg(long, value_enum, default_value_t, conflicts_with = "file_out")]
+    format: Format,
+
     /// The \[Unit\] section
     #[command(flatten)]
     unit: Unit,
@@ -111,6 +154,21 @@ pub struct Cli {
 
 impl Cli {
     pub fn print_or_write_files(self) -> eyre::Result<()> {
+        if let Commands::Reverse { file } = &self.command {
+            if self.unit_directory || self.file.is_some() {
+                eyre::bail!("`reverse` prints a command and does not generate files");
+            }
+            let run: reverse::Run = fs::read_to_string(file)
+                .wrap_err_with(|| format!("Could not read file: {}", file.display()))?
+                .parse()
+                .wrap_err_with(|| format!("Could not convert file: {}", file.display()))?;
+            for key in &run.unknown_keys {
+                eprintln!("Warning: `{key}=` has no `podman run` equivalent, it will be omitted");
+            }
+            println!("{run}");
+            return Ok(());
+        }
+
         if self.unit_directory || self.file.is_some() {
             let path = self.file_path()?;
             if matches!(path, FilePath::Full(..))
@@ -125,7 +183,16 @@ impl Cli {
                 ));
             }
 
+            if self.unit_directory {
+                if let FilePath::Dir(path) = &path {
+                    fs::create_dir_all(path).wrap_err_with(|| {
+                        format!("Could not create unit directory: {}", path.display())
+                    })?;
+                }
+            }
+
             let overwrite = self.overwrite;
+            let daemon_reload = self.daemon_reload.then(|| self.is_system());
             #[cfg(unix)]
             let services_check = !self.skip_services_check;
 
@@ -140,42 +207,38 @@ impl Cli {
                 )?;
             }
 
-            for file in files {
-                let path: Cow<Path> = match &path {
-                    FilePath::Full(path) => path.into(),
-                    FilePath::Dir(path) => {
-                        let mut path = path.join(file.name());
-                        path.set_extension(file.extension());
-                        path.into()
-                    }
-                };
-                file.write(&path, overwrite)?;
+            write_files(files, &path, overwrite)?;
+
+            if let Some(system) = daemon_reload {
+                systemctl_daemon_reload(system, Command::status)?;
             }
 
             Ok(())
         } else {
-            let files = self
-                .try_into_files()?
-                .into_iter()
-                .map(|file| format!("# {}.{}\n{file}", file.name(), file.extension()))
-                .collect::<Vec<_>>()
-                .join("\n---\n\n");
-            print!("{files}");
+            match self.format {
+                Format::Quadlet => print!("{}", combine_files(self.try_into_files()?)),
+                Format::Json => println!("{}", files_to_json(&self.try_into_files()?)?),
+            }
             Ok(())
         }
     }
 
+    /// Returns `true` if podlet is generating files for the system rather than the user
+    fn is_system(&self) -> bool {
+        #[cfg(unix)]
+        let is_root = nix::unistd::Uid::current().is_root();
+        #[cfg(not(unix))]
+        let is_root = false;
+
+        self.system || is_root
+    }
+
     /// Returns the file path for the generated file
     fn file_path(&self) -> eyre::Result<FilePath> {
         let path = if self.unit_directory {
             #[cfg(unix)]
-            if nix::unistd::Uid::current().is_root() {
-                let path = PathBuf::from("/etc/containers/systemd/");
-                if path.is_dir() {
-                    path
-                } else {
-                    PathBuf::from("/usr/share/containers/systemd/")
-                }
+            if self.is_system() {
+                PathBuf::from("/etc/containers/systemd/")
             } else {
                 let mut path: PathBuf = env::var("XDG_CONFIG_HOME")
                     .or_else(|_| env::var("HOME").map(|home| format!("{home}/.config")))
@@ -204,12 +267,28 @@ impl Cli {
     }
 
     fn try_into_files(self) -> color_eyre::Result<Vec<File>> {
-        let unit = (!self.unit.is_empty()).then_some(self.unit);
-        let install = self.install.install.then(|| self.install.into());
+        self.try_into_files_with_compose(None)
+    }
 
-        match self.command {
+    /// Like [`Self::try_into_files()`], but the `compose` command converts `compose`,
+    /// if given, instead of reading the compose file.
+    fn try_into_files_with_compose(
+        self,
+        compose: Option<Compose>,
+    ) -> color_eyre::Result<Vec<File>> {
+        let mut unit = (!self.unit.is_empty()).then_some(self.unit);
+        let install = (!self.install.is_empty()).then(|| self.install.into());
+
+        let mut files = match self.command {
             Commands::Podman { command } => {
-                let service = command.service().cloned();
+                match &command {
+                    PodmanCommands::Run { container, .. } => container.validate()?,
+                    PodmanCommands::Kube { kube } => kube.check_podman_version()?,
+                    _ => {}
+                }
+
+                let service = command.service();
+                add_restart_retries(&mut unit, service.as_ref());
                 let file = quadlet::File {
                     name: self.name.unwrap_or_else(|| String::from(command.name())),
                     unit,
@@ -219,22 +298,49 @@ impl Cli {
                 };
                 Ok(vec![file.into()])
             }
-            Commands::Compose { pod, compose_file } => {
-                let compose = compose_from_file(&compose_file)?;
+            Commands::Compose {
+                quadlet_pod,
+                kube,
+                pod,
+                profile,
+                compose_file,
+            } => {
+                if pod.is_some() {
+                    eprintln!("Warning: `--pod` is deprecated, use `--kube` instead");
+                }
+                let kube = kube.or(pod);
+
+                let mut compose = match compose {
+                    Some(compose) => compose,
+                    None => compose_from_file(compose_file.as_deref())?,
+                };
+                compose::filter_profiles(&mut compose, &profile);
 
                 if !compose.extensions.is_empty() {
                     eyre::bail!("extensions are not supported");
                 }
 
-                if let Some(pod_name) = pod {
+                if let Some(pod_name) = quadlet_pod {
+                    compose_try_into_pod_quadlet_files(
+                        compose,
+                        pod_name,
+                        unit.as_ref(),
+                        install.as_ref(),
+                    )
+                    .map(|files| files.into_iter().map(Into::into).collect())
+                } else if let Some(pod_name) = kube {
                     let (pod, persistent_volume_claims) =
                         k8s::compose_try_into_pod(compose, pod_name.clone())?;
 
                     let kube = quadlet::Kube {
                         config_map: Vec::new(),
+                        exit_code_propagation: None,
                         log_driver: None,
                         network: Vec::new(),
+                        podman_args: None,
+                        podman_version: quadlet::PodmanVersion::default(),
                         publish_port: Vec::new(),
+                        set_working_directory: None,
                         user_ns: None,
                         yaml: format!("{pod_name}-kube.yaml"),
                     };
@@ -256,15 +362,87 @@ impl Cli {
                         },
                     ])
                 } else {
-                    compose_try_into_quadlet_files(compose, &unit, &install)
+                    compose_try_into_quadlet_files(compose, unit.as_ref(), install.as_ref())
                         .map(|result| result.map(Into::into))
                         .collect()
                 }
             }
+            Commands::Reverse { .. } => {
+                eyre::bail!("`reverse` prints a command and does not generate files")
+            }
+        }?;
+
+        if self.auto_description {
+            for file in &mut files {
+                if let File::Quadlet(file) = file {
+                    file.unit
+                        .get_or_insert_with(Unit::default)
+                        .default_description(|| {
+                            format!("Podman {} for {}", file.resource.extension(), file.name)
+                        });
+                }
+            }
         }
+
+        Ok(files)
     }
 }
 
+/// Serializes `files` into a pretty printed JSON array
+fn files_to_json(files: &[File]) -> eyre::Result<String> {
+    serde_json::to_string_pretty(files).wrap_err("Could not serialize files to JSON")
+}
+
+/// Combines `files` into a single string, each with a `# NAME.EXTENSION` header
+fn combine_files(files: Vec<File>) -> String {
+    files
+        .into_iter()
+        .map(|file| format!("# {}.{}\n{file}", file.name(), file.extension()))
+        .collect::<Vec<_>>()
+        .join("\n---\n\n")
+}
+
+/// Reloads the systemd manager configuration so quadlet generates services for the new files
+///
+/// `run` runs the `systemctl` command and returns its exit status.
+fn systemctl_daemon_reload(
+    system: bool,
+    run: impl FnOnce(&mut Command) -> io::Result<ExitStatus>,
+) -> eyre::Result<()> {
+    let mut command = Command::new("systemctl");
+    if !system {
+        command.arg("--user");
+    }
+    command.arg("daemon-reload");
+
+    let status = run(&mut command)
+        .wrap_err("Failed to run `systemctl daemon-reload`")
+        .suggestion("Make sure systemd is installed and `systemctl` is in your PATH.")?;
+    eyre::ensure!(
+        status.success(),
+        "`systemctl daemon-reload` failed with {status}"
+    );
+    println!("Reloaded systemd manager configuration");
+    Ok(())
+}
+
+/// Writes `files` to `path`, naming them after each file if `path` is a directory
+fn write_files(files: Vec<File>, path: &FilePath, overwrite: bool) -> eyre::Result<()> {
+    for file in files {
+        let path: Cow<Path> = match path {
+            FilePath::Full(path) => path.into(),
+            FilePath::Dir(path) => {
+                let mut path = path.join(file.name());
+                path.set_extension(file.extension());
+                path.into()
+            }
+        };
+        file.write(&path, overwrite)?;
+    }
+
+    Ok(())
+}
+
 #[derive(Debug)]
 enum FilePath {
     Full(PathBuf),
@@ -282,8 +460,10 @@ enum Commands {
     /// Generate podman quadlet files from a compose file
     ///
     /// Creates a `.container` file for each service,
+    /// named after the service's `container_name` if set or the service name otherwise,
     /// a `.volume` file for each volume,
     /// and a `.network` file for each network.
+    /// With `--quadlet-pod`, a `.pod` file is also created.
     ///
     /// The `--file` option must be a directory if used.
     ///
@@ -292,21 +472,53 @@ enum Commands {
     /// When podlet encounters an unsupported option, an error will be returned.
     /// Modify the compose file to resolve the error.
     Compose {
+        /// Create a `.pod` file and link it with each `.container` file
+        ///
+        /// Each container's published ports and networks are moved to the pod.
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["kube", "pod"])]
+        quadlet_pod: Option<String>,
+
         /// Create a Kubernetes YAML file for a pod instead of separate containers
         ///
         /// A `.kube` file using the generated Kubernetes YAML file will also be created.
-        #[arg(long)]
+        #[arg(long, value_name = "NAME", conflicts_with = "pod")]
+        kube: Option<String>,
+
+        /// Deprecated, use `--kube`
+        #[arg(long, value_name = "NAME", hide = true)]
         pod: Option<String>,
 
+        /// Enable a compose profile
+        ///
+        /// Services with `profiles` are only converted if one of their profiles is enabled.
+        /// Services without `profiles` are always converted.
+        ///
+        /// Can be specified multiple times
+        #[arg(long, value_name = "PROFILE")]
+        profile: Vec<String>,
+
         /// The compose file to convert
         ///
+        /// If `-`, the compose file is read from stdin.
+        ///
         /// If not provided, podlet will look for (in order)
         /// `compose.yaml`, `compose.yml`, `docker-compose.yaml`, and `docker-compose.yml`,
         /// in the current working directory.
+        /// If none of them exist and stdin is not a terminal, the compose file is read from stdin.
         compose_file: Option<PathBuf>,
     },
+
+    /// Print the `podman run` command equivalent to a `.container` quadlet file
+    ///
+    /// Only the [Container] section is converted.
+    /// Keys without a `podman run` equivalent are omitted with a warning.
+    Reverse {
+        /// The `.container` quadlet file to convert
+        file: PathBuf,
+    },
 }
 
+#[allow(clippy::doc_markdown)]
 #[derive(Subcommand, Debug, Clone, PartialEq)]
 enum PodmanCommands {
     /// Generate a podman quadlet `.container` file
@@ -323,6 +535,41 @@ enum PodmanCommands {
         service: Service,
     },
 
+    /// Generate a podman quadlet `.build` file
+    ///
+    /// For details on options see:
+    /// https://docs.podman.io/en/latest/markdown/podman-build.1.html and
+    /// https://docs.podman.io/en/latest/markdown/podman-systemd.unit.5.html#build-units-build
+    #[group(skip)]
+    Build {
+        /// The \[Build\] section
+        #[command(flatten)]
+        build: Box<Build>,
+    },
+
+    /// Generate a podman quadlet `.image` file
+    ///
+    /// Only options supported by quadlet are present
+    ///
+    /// For details on options see:
+    /// https://docs.podman.io/en/latest/markdown/podman-pull.1.html and
+    /// https://docs.podman.io/en/latest/markdown/podman-systemd.unit.5.html#image-units-image
+    #[group(skip)]
+    Pull {
+        #[command(flatten)]
+        pull: Box<Pull>,
+    },
+
+    /// Generate a podman quadlet `.image` file
+    ///
+    /// For details on options see:
+    /// https://docs.podman.io/en/latest/markdown/podman-image.1.html
+    Image {
+        /// The \[Image\] section
+        #[command(subcommand)]
+        image: Image,
+    },
+
     /// Generate a podman quadlet `.kube` file
     ///
     /// For details on options see:
@@ -343,6 +590,16 @@ enum PodmanCommands {
         network: Network,
     },
 
+    /// Generate a podman quadlet `.pod` file
+    ///
+    /// For details on options see:
+    /// https://docs.podman.io/en/latest/markdown/podman-pod-create.1.html
+    Pod {
+        /// The \[Pod\] section
+        #[command(subcommand)]
+        pod: pod::Pod,
+    },
+
     /// Generate a podman quadlet `.volume` file
     ///
     /// For details on options see:
@@ -370,17 +627,27 @@ impl From<PodmanCommands> for quadlet::Resource {
     fn from(value: PodmanCommands) -> Self {
         match value {
             PodmanCommands::Run { container, .. } => (*container).into(),
+            PodmanCommands::Build { build } => (*build).into(),
+            PodmanCommands::Pull { pull } => (*pull).into(),
+            PodmanCommands::Image { image } => image.into(),
             PodmanCommands::Kube { kube } => kube.into(),
             PodmanCommands::Network { network } => network.into(),
+            PodmanCommands::Pod { pod } => pod.into(),
             PodmanCommands::Volume { volume } => volume.into(),
         }
     }
 }
 
 impl PodmanCommands {
-    fn service(&self) -> Option<&Service> {
+    fn service(&self) -> Option<Service> {
         match self {
-            Self::Run { service, .. } => (!service.is_empty()).then_some(service),
+            Self::Run { container, service } => {
+                let service = service.clone().with_stop(
+                    container.stop_signal().map(String::from),
+                    container.stop_timeout(),
+                );
+                (!service.is_empty()).then_some(service)
+            }
             _ => None,
         }
     }
@@ -389,14 +656,29 @@ impl PodmanCommands {
     fn name(&self) -> &str {
         match self {
             Self::Run { container, .. } => container.name(),
+            Self::Build { build } => build.name(),
+            Self::Pull { pull } => pull.name(),
+            Self::Image { image } => image.name(),
             Self::Kube { kube } => kube.name(),
             Self::Network { network } => network.name(),
+            Self::Pod { pod } => pod.name(),
             Self::Volume { volume } => volume.name(),
         }
     }
 }
 
-#[derive(Debug)]
+/// Output formats for the generated files
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// Quadlet (systemd-like) files
+    #[default]
+    Quadlet,
+    /// A JSON array of the files
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
 #[allow(clippy::large_enum_variant)] // false positive, [Pod] is not zero-sized
 enum File {
     Quadlet(quadlet::File),
@@ -498,92 +780,151 @@ impl File {
 #[derive(Debug)]
 struct ComposeService {
     service: docker_compose_types::Service,
-    volume_has_options: Rc<HashMap<String, bool>>,
+    /// Sources used to reference the compose file's top-level volumes, by volume name
+    volume_sources: Rc<HashMap<String, String>>,
 }
 
 impl ComposeService {
-    fn volume_has_options(&self, volume: &str) -> bool {
-        self.volume_has_options
-            .get(volume)
-            .copied()
-            .unwrap_or_default()
+    /// Returns the source to use when referencing `volume`, if it is a top-level volume
+    fn volume_source(&self, volume: &str) -> Option<&str> {
+        self.volume_sources.get(volume).map(String::as_str)
+    }
+}
+
+/// Returns the name of an external compose volume,
+/// or [`None`] if the volume is not external and should be generated
+fn external_volume_name<'a>(
+    name: &'a str,
+    volume: &'a MapOrEmpty<docker_compose_types::ComposeVolume>,
+) -> Option<&'a str> {
+    match volume {
+        MapOrEmpty::Map(docker_compose_types::ComposeVolume {
+            external: Some(external),
+            ..
+        }) => match external {
+            docker_compose_types::ExternalVolume::Bool(true) => Some(name),
+            docker_compose_types::ExternalVolume::Bool(false) => None,
+            docker_compose_types::ExternalVolume::Name { name } => Some(name),
+        },
+        _ => None,
     }
 }
 
-fn compose_from_file(compose_file: &Option<PathBuf>) -> color_eyre::Result<Compose> {
-    let (compose_file, path) = if let Some(path) = compose_file {
-        let compose_file = fs::File::open(path)
-            .wrap_err("Could not open provided compose file")
-            .suggestion("Make sure you have the proper permissions for the given file.")?;
-        (compose_file, path.display().to_string())
-    } else {
-        let file_names = [
-            "compose.yaml",
-            "compose.yml",
-            "docker-compose.yaml",
-            "docker-compose.yml",
-        ];
-        let mut result = None;
-        for file_name in file_names {
-            if let Ok(compose_file) = fs::File::open(file_name) {
-                result = Some((compose_file, String::from(file_name)));
-                break;
+/// Reads the compose file at `compose_file` or from stdin if it is `-`
+///
+/// If not provided, the first of the default compose files in the current directory is read.
+/// If none exist and stdin is not a terminal, the compose file is read from stdin.
+fn compose_from_file(compose_file: Option<&Path>) -> color_eyre::Result<Compose> {
+    let (compose_file, path): (Box<dyn Read>, _) = match compose_file {
+        Some(path) if path == Path::new("-") => (Box::new(io::stdin()), PathBuf::from("stdin")),
+        Some(path) => {
+            let compose_file = fs::File::open(path)
+                .wrap_err("Could not open provided compose file")
+                .suggestion("Make sure you have the proper permissions for the given file.")?;
+            (Box::new(compose_file), path.to_path_buf())
+        }
+        None => {
+            let file_names = [
+                "compose.yaml",
+                "compose.yml",
+                "docker-compose.yaml",
+                "docker-compose.yml",
+            ];
+            let mut result: Option<(Box<dyn Read>, _)> = None;
+            for file_name in file_names {
+                if let Ok(compose_file) = fs::File::open(file_name) {
+                    result = Some((Box::new(compose_file), PathBuf::from(file_name)));
+                    break;
+                }
+            }
+            if result.is_none() && !io::stdin().is_terminal() {
+                result = Some((Box::new(io::stdin()), PathBuf::from("stdin")));
             }
+            result.ok_or_else(|| {
+                eyre::eyre!(
+                    "A compose file was not provided and none of \
+                    `compose.yaml`, `compose.yml`, `docker-compose.yaml`, or `docker-compose.yml` \
+                    exist in the current directory or could not be read"
+                )
+            })?
         }
-        result.ok_or_else(|| {
-            eyre::eyre!(
-                "A compose file was not provided and none of \
-                `compose.yaml`, `compose.yml`, `docker-compose.yaml`, or `docker-compose.yml` \
-                exist in the current directory or could not be read"
-            )
-        })?
     };
 
-    serde_yaml::from_reader(compose_file)
-        .wrap_err_with(|| format!("File `{path}` is not a valid compose file"))
+    // `extends.file` paths from stdin are relative to the current directory
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    compose_from_reader(compose_file, &path, dir)
+}
+
+/// Reads a compose file from `reader` and resolves its services' `extends`
+///
+/// `path` is used in error messages and `dir` is the directory `extends.file` paths are relative to.
+fn compose_from_reader(reader: impl Read, path: &Path, dir: &Path) -> color_eyre::Result<Compose> {
+    let mut compose = serde_yaml::from_reader(reader)
+        .wrap_err_with(|| format!("File `{}` is not a valid compose file", path.display()))?;
+
+    compose::resolve_extends(&mut compose, dir)?;
+
+    Ok(compose)
 }
 
 fn compose_try_into_quadlet_files<'a>(
     mut compose: Compose,
-    unit: &'a Option<Unit>,
-    install: &'a Option<quadlet::Install>,
+    unit: Option<&'a Unit>,
+    install: Option<&'a quadlet::Install>,
 ) -> impl Iterator<Item = color_eyre::Result<quadlet::File>> + 'a {
-    let volume_has_options = compose
+    let volume_sources = compose
         .volumes
         .0
         .iter()
-        .map(|(name, volume)| (name.clone(), matches!(volume, MapOrEmpty::Map(_))))
+        .map(|(name, volume)| {
+            let source = external_volume_name(name, volume)
+                .map_or_else(|| format!("{name}.volume"), String::from);
+            (name.clone(), source)
+        })
+        .collect();
+    let container_names: HashMap<_, _> = compose
+        .services
+        .0
+        .iter()
+        .filter_map(|(name, service)| {
+            let container_name = service.as_ref()?.container_name.clone()?;
+            Some((name.clone(), container_name))
+        })
         .collect();
     compose_services(&mut compose)
-        .zip(iter::repeat(Rc::new(volume_has_options)))
-        .map(|(result, volume_has_options)| {
+        .zip(iter::repeat((
+            Rc::new(volume_sources),
+            Rc::new(container_names),
+        )))
+        .map(move |(result, (volume_sources, container_names))| {
             result.and_then(|(name, mut service)| {
-                let mut unit = unit.clone();
-                if !service.depends_on.is_empty() {
-                    unit.get_or_insert(Unit::default())
-                        .add_dependencies(mem::take(&mut service.depends_on));
-                }
+                let mut unit = unit.cloned();
+                compose_service_dependencies(&mut service, &mut unit, &container_names);
 
                 let service = ComposeService {
                     service,
-                    volume_has_options,
+                    volume_sources,
                 };
                 let command: PodmanCommands = service.try_into().wrap_err_with(|| {
                     format!("Could not parse service `{name}` as a valid podman command")
                 })?;
 
-                let service = command.service().cloned();
+                let service = command.service();
+                add_restart_retries(&mut unit, service.as_ref());
+
+                // The container's name is used for the file so other services can reference it
+                let name = container_names.get(&name).cloned().unwrap_or(name);
 
                 Ok(quadlet::File {
                     name,
                     unit,
                     resource: command.into(),
                     service,
-                    install: install.clone(),
+                    install: install.cloned(),
                 })
             })
         })
-        .chain(compose.networks.0.into_iter().map(|(name, network)| {
+        .chain(compose.networks.0.into_iter().map(move |(name, network)| {
             let network = Option::<docker_compose_types::NetworkSettings>::from(network)
                 .map(quadlet::Network::try_from)
                 .transpose()
@@ -593,26 +934,121 @@ fn compose_try_into_quadlet_files<'a>(
                 .unwrap_or_default();
             Ok(quadlet::File {
                 name,
-                unit: unit.clone(),
+                unit: unit.cloned(),
                 resource: network.into(),
                 service: None,
-                install: install.clone(),
-            })
-        }))
-        .chain(compose.volumes.0.into_iter().filter_map(|(name, volume)| {
-            Option::<docker_compose_types::ComposeVolume>::from(volume).map(|volume| {
-                let volume = quadlet::Volume::try_from(volume).wrap_err_with(|| {
-                    format!("could not parse volume `{name}` as a valid podman volume")
-                })?;
-                Ok(quadlet::File {
-                    name,
-                    unit: unit.clone(),
-                    resource: volume.into(),
-                    service: None,
-                    install: install.clone(),
-                })
+                install: install.cloned(),
             })
         }))
+        .chain(
+            compose
+                .volumes
+                .0
+                .into_iter()
+                .filter(|(name, volume)| external_volume_name(name, volume).is_none())
+                .map(move |(name, volume)| {
+                    let volume = Option::<docker_compose_types::ComposeVolume>::from(volume)
+                        .map(quadlet::Volume::try_from)
+                        .transpose()
+                        .wrap_err_with(|| {
+                            format!("could not parse volume `{name}` as a valid podman volume")
+                        })?
+                        .unwrap_or_default();
+                    Ok(quadlet::File {
+                        name,
+                        unit: unit.cloned(),
+                        resource: volume.into(),
+                        service: None,
+                        install: install.cloned(),
+                    })
+                }),
+        )
+}
+
+/// Converts the compose file into quadlet files for a pod named `pod_name`,
+/// with its containers linked to a generated `.pod` file
+fn compose_try_into_pod_quadlet_files(
+    compose: Compose,
+    pod_name: String,
+    unit: Option<&Unit>,
+    install: Option<&quadlet::Install>,
+) -> color_eyre::Result<Vec<quadlet::File>> {
+    let mut files: Vec<_> =
+        compose_try_into_quadlet_files(compose, unit, install).collect::<Result<_, _>>()?;
+
+    let mut network = Vec::new();
+    let mut publish_port = Vec::new();
+    for file in &mut files {
+        if let quadlet::Resource::Container(container) = &mut file.resource {
+            container.pod = Some(format!("{pod_name}.pod"));
+            for container_network in mem::take(&mut container.network) {
+                if !network.contains(&container_network) {
+                    network.push(container_network);
+                }
+            }
+            publish_port.append(&mut container.publish_port);
+        }
+    }
+
+    let pod = quadlet::Pod {
+        label: Vec::new(),
+        network,
+        pod_name: pod_name.clone(),
+        publish_port,
+        volume: Vec::new(),
+    };
+    files.push(quadlet::File {
+        name: pod_name,
+        unit: unit.cloned(),
+        resource: pod.into(),
+        service: None,
+        install: install.cloned(),
+    });
+
+    Ok(files)
+}
+
+/// Adds the restart retries of `service` to `unit`, systemd reads `StartLimitBurst=` from `[Unit]`
+fn add_restart_retries(unit: &mut Option<Unit>, service: Option<&Service>) {
+    if let Some(retries) = service.and_then(Service::restart_retries) {
+        unit.get_or_insert(Unit::default())
+            .set_start_limit_burst(retries);
+    }
+}
+
+/// Adds the services `service` depends on to `unit`,
+/// both from `depends_on` and from a `network_mode` of `service:NAME`
+///
+/// `container_names` maps service names with a `container_name` to it.
+fn compose_service_dependencies(
+    service: &mut docker_compose_types::Service,
+    unit: &mut Option<Unit>,
+    container_names: &HashMap<String, String>,
+) {
+    let container_name = |name: String| container_names.get(&name).cloned().unwrap_or(name);
+    if !service.depends_on.is_empty() {
+        unit.get_or_insert(Unit::default())
+            .add_dependencies(mem::take(&mut service.depends_on), container_name);
+    }
+
+    // Join the network namespace of the other service's container,
+    // which quadlet names `systemd-SERVICE` unless `container_name` is set
+    if let Some(network_service) = service
+        .network_mode
+        .as_deref()
+        .and_then(|mode| mode.strip_prefix("service:"))
+        .map(String::from)
+    {
+        let network_container = container_names
+            .get(&network_service)
+            .cloned()
+            .unwrap_or_else(|| format!("systemd-{network_service}"));
+        service.network_mode = Some(format!("container:{network_container}"));
+        unit.get_or_insert(Unit::default()).add_dependencies(
+            docker_compose_types::DependsOnOptions::Simple(vec![network_service]),
+            container_name,
+        );
+    }
 }
 
 fn compose_services(
@@ -637,8 +1073,11 @@ fn compose_services(
         )
 }
 
-/// Takes an image and returns an appropriate default service name
+/// Derives a name from an image reference by stripping the registry, repository path,
+/// tag, and digest, e.g. `docker.io/library/nginx:1.25` becomes `nginx`
 fn image_to_name(image: &str) -> &str {
+    // Remove image digest
+    let image = image.split_once('@').map_or(image, |(image, _)| image);
     let image = image
         .rsplit('/')
         .next()
@@ -692,4 +1131,1045 @@ mod tests {
     fn verify_cli() {
         Cli::command().debug_assert();
     }
+
+    /// Parses `args` as `podlet` arguments and returns the generated files by file name
+    pub(super) fn podlet(args: &[&str]) -> Vec<(String, String)> {
+        Cli::try_parse_from(["podlet"].iter().chain(args))
+            .expect("args are valid")
+            .try_into_files()
+            .expect("files are generated")
+            .iter()
+            .map(|file| {
+                (
+                    format!("{}.{}", file.name(), file.extension()),
+                    file.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Parses `args` as `podlet` arguments and returns the single generated quadlet file
+    pub(super) fn quadlet(args: &[&str]) -> String {
+        let [(_, file)] = podlet(args).try_into().expect("one file is generated");
+        file
+    }
+
+    /// Converts the compose file `yaml` and returns the generated quadlet files by file name
+    fn compose(yaml: &str) -> Vec<(String, String)> {
+        compose_with(&["compose"], yaml)
+    }
+
+    /// Parses `args` as `podlet` arguments, which must include the `compose` command,
+    /// converts the compose file `yaml`, and returns the generated files by file name
+    fn compose_with(args: &[&str], yaml: &str) -> Vec<(String, String)> {
+        let compose = compose_from_reader(yaml.as_bytes(), Path::new("stdin"), Path::new(""))
+            .expect("compose file is valid");
+        Cli::try_parse_from(["podlet"].iter().chain(args))
+            .expect("args are valid")
+            .try_into_files_with_compose(Some(compose))
+            .expect("files are generated")
+            .iter()
+            .map(|file| {
+                (
+                    format!("{}.{}", file.name(), file.extension()),
+                    file.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compose_network() {
+        let files = compose(
+            "
+services:
+  web:
+    image: quay.io/podman/hello
+    networks:
+      - frontend
+networks:
+  frontend:
+    driver: bridge
+    driver_opts:
+      mtu: 1500
+    ipam:
+      config:
+        - subnet: 10.89.1.0/24
+          gateway: 10.89.1.1
+",
+        );
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].0, "web.container");
+        assert!(files[0].1.contains("Network=frontend.network\n"));
+        assert_eq!(
+            files[1],
+            (
+                String::from("frontend.network"),
+                String::from(
+                    "[Network]\n\
+                    Driver=bridge\n\
+                    Gateway=10.89.1.1\n\
+                    Options=mtu=1500\n\
+                    Subnet=10.89.1.0/24\n"
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn compose_volumes() {
+        let files = compose(
+            "
+services:
+  web:
+    image: quay.io/podman/hello
+    volumes:
+      - data:/data
+      - cache:/cache
+      - shared:/shared:ro
+      - ./config:/config
+volumes:
+  data:
+  cache:
+    driver: local
+    driver_opts:
+      type: tmpfs
+      device: tmpfs
+  shared:
+    external: true
+",
+        );
+        let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["web.container", "data.volume", "cache.volume"]);
+
+        let container = &files[0].1;
+        assert!(container.contains("Volume=data.volume:/data\n"));
+        assert!(container.contains("Volume=cache.volume:/cache\n"));
+        assert!(container.contains("Volume=shared:/shared:ro\n"));
+        assert!(container.contains("Volume=./config:/config\n"));
+
+        assert_eq!(files[1].1, "[Volume]\n");
+        assert_eq!(
+            files[2].1,
+            "[Volume]\nDevice=tmpfs\nDriver=local\nType=tmpfs\n"
+        );
+    }
+
+    #[test]
+    fn compose_healthcheck() {
+        let files = compose(
+            r#"
+services:
+  cmd:
+    image: quay.io/podman/hello
+    healthcheck:
+      test: ["CMD", "curl", "-f", "http://localhost"]
+      interval: 30s
+      timeout: 10s
+      retries: 3
+      start_period: 40s
+  shell:
+    image: quay.io/podman/hello
+    healthcheck:
+      test: ["CMD-SHELL", "curl -f http://localhost || exit 1"]
+  disabled:
+    image: quay.io/podman/hello
+    healthcheck:
+      disable: true
+"#,
+        );
+        assert!(files[0].1.contains(
+            "HealthCmd=[\"curl\", \"-f\", \"http://localhost\"]\n\
+            HealthInterval=30s\n\
+            HealthRetries=3\n\
+            HealthStartPeriod=40s\n\
+            HealthTimeout=10s\n"
+        ));
+        assert!(files[1]
+            .1
+            .contains("HealthCmd=curl -f http://localhost || exit 1\n"));
+        assert!(files[2].1.contains("HealthCmd=none\n"));
+    }
+
+    #[test]
+    fn compose_deploy_resources() {
+        let files = compose(
+            r#"
+services:
+  app:
+    image: quay.io/podman/hello
+    deploy:
+      resources:
+        limits:
+          cpus: "0.5"
+          memory: 512M
+        reservations:
+          memory: 128M
+"#,
+        );
+        assert!(files[0]
+            .1
+            .contains("PodmanArgs=--cpus 0.5 --memory 512M --memory-reservation 128M\n"));
+    }
+
+    #[test]
+    fn compose_deploy_unsupported() {
+        let compose = serde_yaml::from_str(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    deploy:
+      replicas: 2
+",
+        )
+        .expect("compose file is valid");
+        let error = compose_try_into_quadlet_files(compose, None, None)
+            .next()
+            .expect("compose file has a service")
+            .expect_err("deploy.replicas is unsupported");
+        assert!(format!("{error:?}").contains("deploy.replicas"));
+    }
+
+    #[test]
+    fn compose_env_file() {
+        let files = compose(
+            "
+services:
+  single:
+    image: quay.io/podman/hello
+    env_file: .env
+  list:
+    image: quay.io/podman/hello
+    env_file:
+      - ./common.env
+      - /etc/app/app.env
+",
+        );
+        assert!(files[0].1.contains("EnvironmentFile=.env\n"));
+        assert!(files[1].1.contains(
+            "EnvironmentFile=./common.env\n\
+            EnvironmentFile=/etc/app/app.env\n"
+        ));
+    }
+
+    #[test]
+    fn compose_restart() {
+        let files = compose(
+            r#"
+services:
+  always:
+    image: quay.io/podman/hello
+    restart: always
+  unless-stopped:
+    image: quay.io/podman/hello
+    restart: unless-stopped
+  on-failure:
+    image: quay.io/podman/hello
+    restart: on-failure:3
+  no:
+    image: quay.io/podman/hello
+    restart: "no"
+"#,
+        );
+        for ((name, file), expected) in files.iter().zip([
+            "Restart=always\n",
+            "Restart=always\n",
+            "Restart=on-failure\n",
+            "Restart=no\n",
+        ]) {
+            assert!(
+                file.ends_with(&format!("[Service]\n{expected}")),
+                "{name}: {file}"
+            );
+        }
+
+        assert!(files[2]
+            .1
+            .starts_with("[Unit]\nStartLimitBurst=3\n\n[Container]\n"));
+    }
+
+    #[test]
+    fn compose_labels() {
+        let files = compose(
+            "
+services:
+  list:
+    image: quay.io/podman/hello
+    labels:
+      - app=web
+      - com.example.tier=frontend
+  map:
+    image: quay.io/podman/hello
+    labels:
+      com.example.tier: frontend
+      app: web
+",
+        );
+        let expected = "Label=app=web\nLabel=com.example.tier=frontend\n";
+        assert!(files[0].1.contains(expected));
+        assert!(files[1].1.contains(expected));
+    }
+
+    #[test]
+    fn compose_cap_add() {
+        let files = compose(
+            "
+services:
+  net:
+    image: quay.io/podman/hello
+    cap_add:
+      - NET_ADMIN
+      - NET_RAW
+  all:
+    image: quay.io/podman/hello
+    cap_add: [ALL]
+",
+        );
+        assert!(files[0].1.contains("AddCapability=NET_ADMIN NET_RAW\n"));
+        assert!(files[1].1.contains("AddCapability=ALL\n"));
+    }
+
+    #[test]
+    fn compose_sysctls() {
+        let files = compose(
+            "
+services:
+  map:
+    image: quay.io/podman/hello
+    sysctls:
+      net.core.somaxconn: 1024
+      net.ipv4.tcp_syncookies: 0
+  list:
+    image: quay.io/podman/hello
+    sysctls:
+      - net.core.somaxconn=1024
+      - net.ipv4.tcp_syncookies=0
+",
+        );
+        let expected = "Sysctl=net.core.somaxconn=1024\nSysctl=net.ipv4.tcp_syncookies=0\n";
+        assert!(files[0].1.contains(expected));
+        assert!(files[1].1.contains(expected));
+    }
+
+    #[test]
+    fn compose_devices() {
+        let files = compose(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    devices:
+      - /dev/ttyUSB0:/dev/ttyUSB0:rwm
+      - /dev/dri
+",
+        );
+        assert!(files[0]
+            .1
+            .contains("AddDevice=/dev/ttyUSB0:/dev/ttyUSB0:rwm\nAddDevice=/dev/dri\n"));
+    }
+
+    #[test]
+    fn compose_tmpfs() {
+        let files = compose(
+            "
+services:
+  single:
+    image: quay.io/podman/hello
+    tmpfs: /run
+  list:
+    image: quay.io/podman/hello
+    tmpfs:
+      - /run
+      - /tmp:rw,size=64m
+",
+        );
+        assert!(files[0].1.contains("Tmpfs=/run\n"));
+        assert!(files[1].1.contains("Tmpfs=/run\nTmpfs=/tmp:rw,size=64m\n"));
+    }
+
+    #[test]
+    fn compose_dns() {
+        let files = compose(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    dns:
+      - 1.1.1.1
+      - 9.9.9.9
+",
+        );
+        assert!(files[0].1.contains("DNS=1.1.1.1\nDNS=9.9.9.9\n"));
+    }
+
+    #[test]
+    fn compose_ulimits() {
+        let files = compose(
+            "
+services:
+  flat:
+    image: quay.io/podman/hello
+    ulimits:
+      nofile: 1024
+  nested:
+    image: quay.io/podman/hello
+    ulimits:
+      nofile:
+        soft: 1024
+        hard: 2048
+",
+        );
+        assert!(files[0].1.contains("Ulimit=nofile=1024\n"));
+        assert!(files[1].1.contains("Ulimit=nofile=1024:2048\n"));
+    }
+
+    #[test]
+    fn compose_logging() {
+        let files = compose(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    logging:
+      driver: json-file
+      options:
+        max-size: 10m
+        max-file: 3
+",
+        );
+        assert!(files[0]
+            .1
+            .contains("LogDriver=json-file\nLogOpt=max-size=10m\nLogOpt=max-file=3\n"));
+    }
+
+    #[test]
+    fn compose_command_and_entrypoint() {
+        let files = compose(
+            r#"
+services:
+  string:
+    image: quay.io/podman/hello
+    command: echo "hello world"
+  list:
+    image: quay.io/podman/hello
+    command: [echo, hello world]
+  entrypoint:
+    image: quay.io/podman/hello
+    entrypoint: /bin/sh -c
+    command: [echo hello]
+"#,
+        );
+        assert!(files[0].1.contains("Exec=echo \"hello world\"\n"));
+        assert!(files[1].1.contains("Exec=echo \"hello world\"\n"));
+        assert!(files[2].1.contains("Entrypoint=/bin/sh -c\n"));
+        assert!(files[2].1.contains("Exec=\"echo hello\"\n"));
+    }
+
+    #[test]
+    fn compose_user() {
+        let files = compose(
+            r#"
+services:
+  ids:
+    image: quay.io/podman/hello
+    user: "1000:1000"
+  names:
+    image: quay.io/podman/hello
+    user: app:app
+  none:
+    image: quay.io/podman/hello
+"#,
+        );
+        assert!(files[0].1.contains("User=1000:1000\n"));
+        assert!(files[1].1.contains("User=app:app\n"));
+        assert!(!files[2].1.contains("User="));
+    }
+
+    #[test]
+    fn compose_working_dir() {
+        let files = compose(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    working_dir: /app
+",
+        );
+        assert!(files[0].1.contains("WorkingDir=/app\n"));
+    }
+
+    #[test]
+    fn compose_hostname() {
+        let files = compose(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    hostname: app.example.com
+",
+        );
+        assert!(files[0].1.contains("HostName=app.example.com\n"));
+        assert!(!files[0].1.contains("PodmanArgs="));
+    }
+
+    #[test]
+    fn compose_extra_hosts() {
+        let files = compose(
+            r#"
+services:
+  app:
+    image: quay.io/podman/hello
+    extra_hosts:
+      - "db:10.0.0.5"
+      - "cache:10.0.0.6"
+"#,
+        );
+        assert!(files[0]
+            .1
+            .contains("AddHost=db:10.0.0.5\nAddHost=cache:10.0.0.6\n"));
+    }
+
+    #[test]
+    fn compose_expose() {
+        let files = compose(
+            r#"
+services:
+  app:
+    image: quay.io/podman/hello
+    expose:
+      - "3000"
+      - "8000"
+"#,
+        );
+        assert!(files[0]
+            .1
+            .contains("ExposeHostPort=3000\nExposeHostPort=8000\n"));
+        assert!(!files[0].1.contains("PublishPort="));
+    }
+
+    #[test]
+    fn compose_long_ports() {
+        let files = compose(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    ports:
+      - target: 80
+        published: 8080
+        protocol: tcp
+        mode: host
+      - target: 443
+        host_ip: 127.0.0.1
+        published: 8443
+      - target: 53
+        host_ip: 127.0.0.1
+        protocol: udp
+",
+        );
+        assert!(files[0].1.contains(
+            "PublishPort=8080:80/tcp\n\
+            PublishPort=127.0.0.1:8443:443\n\
+            PublishPort=127.0.0.1::53/udp\n"
+        ));
+    }
+
+    #[test]
+    fn compose_privileged() {
+        let files = compose(
+            "
+services:
+  privileged:
+    image: quay.io/podman/hello
+    privileged: true
+  unprivileged:
+    image: quay.io/podman/hello
+    privileged: false
+",
+        );
+        assert!(files[0].1.contains("PodmanArgs=--privileged\n"));
+        assert!(!files[1].1.contains("PodmanArgs="));
+    }
+
+    #[test]
+    fn compose_shm_size() {
+        let files = compose(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    shm_size: 256m
+",
+        );
+        assert!(files[0].1.contains("ShmSize=256m\n"));
+    }
+
+    #[test]
+    fn compose_init() {
+        let files = compose(
+            "
+services:
+  init:
+    image: quay.io/podman/hello
+    init: true
+  no-init:
+    image: quay.io/podman/hello
+",
+        );
+        assert!(files[0].1.contains("RunInit=true\n"));
+        assert!(!files[1].1.contains("RunInit="));
+    }
+
+    #[test]
+    fn compose_stop_signal_and_grace_period() {
+        let files = compose(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    stop_signal: SIGINT
+    stop_grace_period: 1m30s
+",
+        );
+        assert!(files[0]
+            .1
+            .contains("PodmanArgs=--stop-signal SIGINT --stop-timeout 90\n"));
+        assert!(files[0]
+            .1
+            .ends_with("[Service]\nKillSignal=SIGINT\nTimeoutStopSec=90\n"));
+    }
+
+    #[test]
+    fn compose_host_network_with_ports() {
+        let compose = serde_yaml::from_str(
+            "
+services:
+  app:
+    image: quay.io/podman/hello
+    network_mode: host
+    ports:
+      - 80:80
+",
+        )
+        .expect("compose file is valid");
+        let error = compose_try_into_quadlet_files(compose, None, None)
+            .collect::<Result<Vec<_>, _>>()
+            .expect_err("host network conflicts with published ports");
+        assert!(format!("{error:?}").contains("`--network host` conflicts with `--publish`"));
+    }
+
+    #[test]
+    fn compose_container_name() {
+        let files = compose(
+            "
+services:
+  web:
+    image: quay.io/podman/hello
+    depends_on:
+      - db
+  db:
+    image: quay.io/podman/hello
+    container_name: postgres
+",
+        );
+        assert_eq!(files[0].0, "web.container");
+        assert!(files[0].1.contains("Requires=postgres.service\n"));
+        assert_eq!(files[1].0, "postgres.container");
+        assert!(files[1].1.contains("ContainerName=postgres\n"));
+    }
+
+    #[test]
+    fn compose_namespaces() {
+        let files = compose(
+            "
+services:
+  host:
+    image: quay.io/podman/hello
+    network_mode: host
+    pid: host
+    ipc: host
+  container:
+    image: quay.io/podman/hello
+    network_mode: container:vpn
+  service:
+    image: quay.io/podman/hello
+    network_mode: service:host
+",
+        );
+        assert!(files[0].1.contains("Network=host\n"));
+        assert!(files[0].1.contains("PodmanArgs=--ipc host --pid host\n"));
+        assert!(files[1].1.contains("Network=container:vpn\n"));
+        assert!(files[2]
+            .1
+            .starts_with("[Unit]\nRequires=host.service\nAfter=host.service\n"));
+        assert!(files[2].1.contains("Network=container:systemd-host\n"));
+    }
+
+    #[test]
+    fn compose_pod() {
+        let compose = serde_yaml::from_str(
+            r#"
+services:
+  web:
+    image: docker.io/library/nginx
+    ports:
+      - "8080:80"
+    networks:
+      - frontend
+  db:
+    image: docker.io/library/postgres
+    networks:
+      - frontend
+networks:
+  frontend:
+"#,
+        )
+        .expect("compose file is valid");
+        let files: Vec<_> =
+            compose_try_into_pod_quadlet_files(compose, String::from("app"), None, None)
+                .expect("compose file converts")
+                .into_iter()
+                .map(|file| {
+                    (
+                        format!("{}.{}", file.name, file.resource.extension()),
+                        file.to_string(),
+                    )
+                })
+                .collect();
+
+        let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "web.container",
+                "db.container",
+                "frontend.network",
+                "app.pod"
+            ]
+        );
+        for (_, container) in &files[..2] {
+            assert!(container.contains("Pod=app.pod\n"));
+            assert!(!container.contains("PublishPort="));
+            assert!(!container.contains("Network="));
+        }
+        assert_eq!(
+            files[3].1,
+            "[Pod]\n\
+            Network=frontend.network\n\
+            PodName=app\n\
+            PublishPort=8080:80\n"
+        );
+    }
+
+    #[test]
+    fn compose_reader() {
+        let yaml = "
+services:
+  app:
+    image: quay.io/podman/hello
+";
+        let compose = compose_from_reader(yaml.as_bytes(), Path::new("stdin"), Path::new(""))
+            .expect("compose file is valid");
+        let files: Vec<_> = compose_try_into_quadlet_files(compose, None, None)
+            .map(|file| file.expect("compose file converts").to_string())
+            .collect();
+        assert_eq!(files, ["[Container]\nImage=quay.io/podman/hello\n"]);
+    }
+
+    #[test]
+    fn write_files_to_directory() {
+        let dir = env::temp_dir().join(format!("podlet-write-files-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("temp directory can be created");
+        let path = FilePath::Dir(dir.clone());
+
+        for args in [
+            &["podlet", "podman", "run", "quay.io/podman/hello"][..],
+            &["podlet", "podman", "kube", "play", "pod.yaml"],
+        ] {
+            let files = || {
+                Cli::try_parse_from(args)
+                    .expect("args are valid")
+                    .try_into_files()
+                    .expect("files are generated")
+            };
+            write_files(files(), &path, false).expect("files are written");
+            assert!(
+                write_files(files(), &path, false).is_err(),
+                "existing files are not overwritten"
+            );
+        }
+
+        let hello = fs::read_to_string(dir.join("hello.container")).expect("file was written");
+        assert_eq!(hello, "[Container]\nImage=quay.io/podman/hello\n");
+        let pod = fs::read_to_string(dir.join("pod.kube")).expect("file was written");
+        assert_eq!(pod, "[Kube]\nYaml=pod.yaml\n");
+
+        fs::remove_dir_all(dir).expect("temp directory can be removed");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn daemon_reload() {
+        use std::os::unix::process::ExitStatusExt;
+
+        for (system, expected) in [
+            (false, &["--user", "daemon-reload"][..]),
+            (true, &["daemon-reload"]),
+        ] {
+            systemctl_daemon_reload(system, |command| {
+                assert_eq!(command.get_program(), "systemctl");
+                assert_eq!(command.get_args().collect::<Vec<_>>(), expected);
+                Ok(ExitStatus::from_raw(0))
+            })
+            .expect("daemon-reload succeeds");
+        }
+
+        assert!(systemctl_daemon_reload(false, |_| Ok(ExitStatus::from_raw(1 << 8))).is_err());
+    }
+
+    #[test]
+    fn combine_compose_files() {
+        let compose = serde_yaml::from_str(
+            "
+services:
+  web:
+    image: docker.io/library/nginx
+  db:
+    image: docker.io/library/postgres
+",
+        )
+        .expect("compose file is valid");
+        let files = compose_try_into_quadlet_files(compose, None, None)
+            .map(|file| file.map(File::from))
+            .collect::<Result<_, _>>()
+            .expect("compose file converts");
+        assert_eq!(
+            combine_files(files),
+            "# web.container\n\
+            [Container]\n\
+            Image=docker.io/library/nginx\n\
+            \n---\n\n\
+            # db.container\n\
+            [Container]\n\
+            Image=docker.io/library/postgres\n"
+        );
+    }
+
+    #[test]
+    fn compose_map_ordering() {
+        let yaml = "
+services:
+  app:
+    image: quay.io/podman/hello
+    environment:
+      ZONE: eu
+      APP_ENV: production
+      LOG_LEVEL: debug
+    labels:
+      org.example.team: ops
+      app: web
+      com.example.tier: frontend
+    sysctls:
+      net.ipv4.tcp_syncookies: 0
+      net.core.somaxconn: 1024
+";
+        let files = compose(yaml);
+        assert_eq!(files, compose(yaml));
+        let file = &files[0].1;
+        assert!(file.contains("Environment=APP_ENV=production LOG_LEVEL=debug ZONE=eu\n"));
+        assert!(file.contains(
+            "Label=app=web\nLabel=com.example.tier=frontend\nLabel=org.example.team=ops\n"
+        ));
+        assert!(file.contains("Sysctl=net.core.somaxconn=1024\nSysctl=net.ipv4.tcp_syncookies=0\n"));
+    }
+
+    #[test]
+    fn run_file_name() {
+        let files = |args: &[&str]| {
+            let files = Cli::try_parse_from(args)
+                .expect("args are valid")
+                .try_into_files()
+                .expect("files are generated");
+            let [File::Quadlet(file)] = files.as_slice() else {
+                panic!("one quadlet file is generated");
+            };
+            (file.name.clone(), file.to_string())
+        };
+
+        let (name, file) = files(&[
+            "podlet",
+            "podman",
+            "run",
+            "--name",
+            "foo",
+            "quay.io/podman/hello",
+        ]);
+        assert_eq!(name, "foo");
+        assert!(file.contains("ContainerName=foo\n"));
+
+        let (name, file) = files(&["podlet", "podman", "run", "quay.io/podman/hello"]);
+        assert_eq!(name, "hello");
+        assert!(!file.contains("ContainerName="));
+
+        // `podlet --name` only overrides the file name
+        let (name, file) = files(&[
+            "podlet",
+            "--file",
+            "--name",
+            "bar",
+            "podman",
+            "run",
+            "--name",
+            "foo",
+            "quay.io/podman/hello",
+        ]);
+        assert_eq!(name, "bar");
+        assert!(file.contains("ContainerName=foo\n"));
+    }
+
+    #[test]
+    fn auto_description() {
+        let files = |args: &[&str]| {
+            Cli::try_parse_from(args)
+                .expect("args are valid")
+                .try_into_files()
+                .expect("files are generated")
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        let sut = files(&[
+            "podlet",
+            "--auto-description",
+            "podman",
+            "run",
+            "docker.io/library/nginx",
+        ]);
+        assert!(sut[0].starts_with("[Unit]\nDescription=Podman container for nginx\n\n"));
+
+        let sut = files(&["podlet", "podman", "run", "docker.io/library/nginx"]);
+        assert!(!sut[0].contains("Description="));
+
+        let sut = files(&[
+            "podlet",
+            "--auto-description",
+            "--description",
+            "Web server",
+            "podman",
+            "run",
+            "docker.io/library/nginx",
+        ]);
+        assert!(sut[0].contains("Description=Web server\n"));
+        assert!(!sut[0].contains("Podman container"));
+    }
+
+    #[test]
+    fn compose_auto_description() {
+        let files = compose_with(
+            &["--auto-description", "compose"],
+            "
+services:
+  web:
+    image: docker.io/library/nginx
+",
+        );
+        assert!(files[0]
+            .1
+            .starts_with("[Unit]\nDescription=Podman container for web\n"));
+    }
+
+    #[test]
+    fn compose_pod_flags() {
+        let files = |flag: &str| {
+            compose_with(
+                &["compose", flag, "app"],
+                "
+services:
+  web:
+    image: docker.io/library/nginx
+",
+            )
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>()
+        };
+
+        // `--pod` is a deprecated alias of `--kube`
+        assert_eq!(files("--kube"), ["app.kube", "app-kube.yaml"]);
+        assert_eq!(files("--pod"), ["app.kube", "app-kube.yaml"]);
+        assert_eq!(files("--quadlet-pod"), ["web.container", "app.pod"]);
+    }
+
+    #[test]
+    fn json_format() {
+        let files = Cli::try_parse_from([
+            "podlet",
+            "--format",
+            "json",
+            "podman",
+            "run",
+            "--name",
+            "web",
+            "quay.io/podman/hello",
+        ])
+        .expect("args are valid")
+        .try_into_files()
+        .expect("files are generated");
+        let json: serde_json::Value =
+            serde_json::from_str(&files_to_json(&files).expect("files serialize"))
+                .expect("output is valid JSON");
+
+        let file = &json[0];
+        assert_eq!(file["name"], "web");
+        let container = &file["resource"]["container"];
+        assert_eq!(container["image"], "quay.io/podman/hello");
+        assert_eq!(container["container_name"], "web");
+    }
+
+    #[test]
+    fn conflicting_network_options() {
+        let parse =
+            |args: &[&str]| Cli::try_parse_from(["podlet", "podman", "run"].iter().chain(args));
+
+        let error = parse(&[
+            "--network",
+            "host",
+            "--publish",
+            "80:80",
+            "quay.io/podman/hello",
+        ])
+        .expect("args are valid")
+        .try_into_files()
+        .expect_err("host network conflicts with published ports");
+        assert!(error
+            .to_string()
+            .contains("`--network host` conflicts with `--publish`"));
+
+        parse(&[
+            "--network",
+            "bridge",
+            "--publish",
+            "80:80",
+            "quay.io/podman/hello",
+        ])
+        .expect("args are valid")
+        .try_into_files()
+        .expect("bridge network can publish ports");
+
+        for args in [
+            &[
+                "--pod",
+                "app",
+                "--network",
+                "bridge",
+                "quay.io/podman/hello",
+            ][..],
+            &["--pod", "app", "--publish", "80:80", "quay.io/podman/hello"],
+        ] {
+            let error = parse(args).expect_err("pod conflicts with network options");
+            assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+        }
+    }
 }
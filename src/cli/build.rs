@@ -0,0 +1,193 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use super::image_to_name;
+
+#[allow(clippy::doc_markdown)]
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct Build {
+    /// Add an annotation to the image metadata
+    ///
+    /// Converts to "Annotation=KEY=VALUE"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "KEY=VALUE")]
+    annotation: Vec<String>,
+
+    /// Override the architecture of the image to be built
+    ///
+    /// Converts to "Arch=ARCH"
+    #[arg(long)]
+    arch: Option<String>,
+
+    /// Path of the authentication file
+    ///
+    /// Converts to "AuthFile=PATH"
+    #[arg(long, value_name = "PATH")]
+    authfile: Option<PathBuf>,
+
+    /// Set environment variables for the build
+    ///
+    /// Converts to "Environment=ENV"
+    ///
+    /// Can be specified multiple times
+    #[arg(long)]
+    env: Vec<String>,
+
+    /// Specify a Containerfile which contains instructions for building the image
+    ///
+    /// Converts to "File=CONTAINERFILE"
+    #[arg(short, long, value_name = "CONTAINERFILE")]
+    file: Option<String>,
+
+    /// Add an image label to the image metadata
+    ///
+    /// Converts to "Label=KEY=VALUE"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "KEY=VALUE")]
+    label: Vec<String>,
+
+    /// Set the network mode for the RUN instructions of the build
+    ///
+    /// Converts to "Network=MODE"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, visible_alias = "net", value_name = "MODE")]
+    network: Vec<String>,
+
+    /// The image pull policy
+    ///
+    /// Accepted values are `always`, `missing`, `never`, and `newer`
+    ///
+    /// Converts to "Pull=POLICY"
+    #[arg(long, value_name = "POLICY")]
+    pull: Option<String>,
+
+    /// The name assigned to the resulting image
+    ///
+    /// Converts to "ImageTag=IMAGE_NAME"
+    ///
+    /// The name of the image, without the registry or tag, is used as the name of the generated
+    /// file when used with the --file option without a filename
+    #[arg(short, long, value_name = "IMAGE_NAME")]
+    tag: String,
+
+    /// Set the target build stage to build
+    ///
+    /// Converts to "Target=STAGE"
+    #[arg(long, value_name = "STAGE")]
+    target: Option<String>,
+
+    /// Require HTTPS and verify certificates when contacting registries
+    ///
+    /// Converts to "TLSVerify=BOOL"
+    #[arg(long, value_name = "BOOL")]
+    tls_verify: Option<bool>,
+
+    /// The build context directory or URL
+    ///
+    /// Converts to "SetWorkingDirectory=CONTEXT"
+    context: Option<String>,
+}
+
+impl From<Build> for crate::quadlet::Build {
+    fn from(value: Build) -> Self {
+        Self {
+            annotation: value.annotation,
+            arch: value.arch,
+            auth_file: value.authfile,
+            environment: value.env,
+            file: value.file,
+            image_tag: value.tag,
+            label: value.label,
+            network: value.network,
+            pull: value.pull,
+            set_working_directory: value.context,
+            target: value.target,
+            tls_verify: value.tls_verify,
+        }
+    }
+}
+
+impl From<Build> for crate::quadlet::Resource {
+    fn from(value: Build) -> Self {
+        crate::quadlet::Build::from(value).into()
+    }
+}
+
+impl Build {
+    pub fn name(&self) -> &str {
+        image_to_name(&self.tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::cli::{tests::podlet, Cli};
+
+    #[test]
+    fn tag_file_context() {
+        let files = podlet(&[
+            "podman",
+            "build",
+            "-t",
+            "myimg:latest",
+            "-f",
+            "Containerfile",
+            ".",
+        ]);
+        assert_eq!(
+            files,
+            [(
+                String::from("myimg.build"),
+                String::from(
+                    "[Build]\n\
+                    ImageTag=myimg:latest\n\
+                    File=Containerfile\n\
+                    SetWorkingDirectory=.\n"
+                )
+            )]
+        );
+    }
+
+    #[test]
+    fn metadata_and_network() {
+        let files = podlet(&[
+            "podman",
+            "build",
+            "--tag",
+            "registry.example.com/app/web:1.0",
+            "--annotation",
+            "org.opencontainers.image.source=https://example.com",
+            "--label",
+            "app=web",
+            "--network",
+            "host",
+            "--target",
+            "release",
+        ]);
+        assert_eq!(
+            files,
+            [(
+                String::from("web.build"),
+                String::from(
+                    "[Build]\n\
+                    ImageTag=registry.example.com/app/web:1.0\n\
+                    Annotation=org.opencontainers.image.source=https://example.com\n\
+                    Label=app=web\n\
+                    Network=host\n\
+                    Target=release\n"
+                )
+            )]
+        );
+    }
+
+    #[test]
+    fn tag_is_required() {
+        assert!(Cli::try_parse_from(["podlet", "podman", "build", "."]).is_err());
+    }
+}
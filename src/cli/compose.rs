@@ -0,0 +1,341 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{self, Context},
+    Help,
+};
+use docker_compose_types::{Compose, DependsOnOptions, Service};
+use indexmap::IndexMap;
+use serde_yaml::Value;
+
+/// Resolves the `extends` option of all of the compose file's services
+///
+/// `dir` is the directory `extends.file` paths are relative to,
+/// normally the directory containing the compose file.
+///
+/// The extending service is merged on top of the service it extends.
+/// Mappings, like `environment` or `labels` in their map form, are merged key by key.
+/// Sequences, like `ports` or `volumes`, are concatenated.
+/// All other options, as well as `command` and `entrypoint`, are overridden.
+pub fn resolve_extends(compose: &mut Compose, dir: &Path) -> eyre::Result<()> {
+    let services = compose.services.0.clone();
+    for (name, service) in &mut compose.services.0 {
+        if let Some(service) = service {
+            if !service.extends.is_empty() {
+                *service = resolve_service(&services, None, dir, name, &mut Vec::new())
+                    .wrap_err_with(|| format!("Could not resolve `extends` of service `{name}`"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Removes the compose file's services that are not enabled by `profiles`
+///
+/// Services without any profiles are always enabled.
+/// Removed services are also removed from the `depends_on` of the remaining services.
+pub fn filter_profiles(compose: &mut Compose, profiles: &[String]) {
+    let services = &mut compose.services.0;
+    services.retain(|_, service| {
+        let disabled = service.as_ref().is_some_and(|service| {
+            !service.profiles.is_empty()
+                && !service
+                    .profiles
+                    .iter()
+                    .any(|profile| profiles.contains(profile))
+        });
+        !disabled
+    });
+
+    let names: Vec<_> = services.keys().cloned().collect();
+    for service in services.values_mut().flatten() {
+        service.profiles.clear();
+        match &mut service.depends_on {
+            DependsOnOptions::Simple(depends_on) => {
+                depends_on.retain(|name| names.contains(name));
+            }
+            DependsOnOptions::Conditional(depends_on) => {
+                depends_on.retain(|name, _| names.contains(name));
+            }
+        }
+    }
+}
+
+/// Returns the service `name` from `services` with its `extends` resolved
+///
+/// `file` is the canonicalized path of the file `services` are from,
+/// or `None` for the compose file being converted, which may be read from stdin.
+///
+/// `stack` holds the file and name of the services currently being resolved,
+/// and is used to detect cycles.
+fn resolve_service(
+    services: &IndexMap<String, Option<Service>>,
+    file: Option<&Path>,
+    dir: &Path,
+    name: &str,
+    stack: &mut Vec<(Option<PathBuf>, String)>,
+) -> eyre::Result<Service> {
+    let key = (file.map(Path::to_path_buf), String::from(name));
+    if stack.contains(&key) {
+        let cycle: Vec<_> = stack
+            .iter()
+            .chain([&key])
+            .map(|(_, name)| name.as_str())
+            .collect();
+        eyre::bail!("cyclic `extends`: {}", cycle.join(" -> "));
+    }
+
+    let mut service = services
+        .get(name)
+        .ok_or_else(|| eyre::eyre!("service `{name}` does not exist"))?
+        .clone()
+        .unwrap_or_default();
+    if service.extends.is_empty() {
+        return Ok(service);
+    }
+
+    let mut extends = std::mem::take(&mut service.extends);
+    let base_name = extends
+        .shift_remove("service")
+        .ok_or_else(|| eyre::eyre!("`extends` of service `{name}` requires `service`"))?;
+    let base_file = extends.shift_remove("file");
+    if let Some(option) = extends.keys().next() {
+        eyre::bail!("`extends.{option}` is unsupported");
+    }
+
+    stack.push(key);
+    let base = if let Some(base_file) = base_file {
+        let path = dir.join(base_file);
+        let (path, reader) = path
+            .canonicalize()
+            .and_then(|path| fs::File::open(&path).map(|reader| (path, reader)))
+            .wrap_err_with(|| format!("Could not open extended file `{}`", path.display()))
+            .suggestion("`extends.file` paths are relative to the compose file.")?;
+        let compose: Compose = serde_yaml::from_reader(reader)
+            .wrap_err_with(|| format!("File `{}` is not a valid compose file", path.display()))?;
+        let dir = path.parent().unwrap_or(dir);
+        resolve_service(&compose.services.0, Some(&path), dir, &base_name, stack)
+    } else {
+        resolve_service(services, file, dir, &base_name, stack)
+    }?;
+    stack.pop();
+
+    merge(base, service)
+}
+
+/// Merges `service` on top of `base`, with `service` taking precedence
+fn merge(base: Service, service: Service) -> eyre::Result<Service> {
+    let mut base = serde_yaml::to_value(base)?;
+    merge_values(&mut base, serde_yaml::to_value(service)?);
+    serde_yaml::from_value(base).wrap_err("merged service is invalid")
+}
+
+fn merge_values(base: &mut Value, value: Value) {
+    match (base, value) {
+        (Value::Mapping(base), Value::Mapping(mapping)) => {
+            for (key, value) in mapping {
+                match base.get_mut(&key) {
+                    Some(base) if !matches!(key.as_str(), Some("command" | "entrypoint")) => {
+                        merge_values(base, value);
+                    }
+                    _ => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Sequence(base), Value::Sequence(sequence)) => {
+            for value in sequence {
+                if !base.contains(&value) {
+                    base.push(value);
+                }
+            }
+        }
+        (base, value) => *base = value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(yaml: &str) -> eyre::Result<Compose> {
+        let mut compose = serde_yaml::from_str(yaml).expect("compose file is valid");
+        resolve_extends(&mut compose, Path::new(".")).map(|()| compose)
+    }
+
+    #[test]
+    fn same_file() {
+        let compose = resolve(
+            "
+services:
+  base:
+    image: quay.io/podman/hello
+    ports:
+      - 8080:80
+  web:
+    extends:
+      service: base
+    ports:
+      - 8443:443
+",
+        )
+        .expect("extends resolves");
+        let web = compose.services.0["web"].as_ref().unwrap();
+        assert!(web.extends.is_empty());
+        assert_eq!(web.image.as_deref(), Some("quay.io/podman/hello"));
+        assert_eq!(
+            web.ports,
+            docker_compose_types::Ports::Short(vec![
+                String::from("8080:80"),
+                String::from("8443:443")
+            ])
+        );
+    }
+
+    #[test]
+    fn local_takes_precedence() {
+        let compose = resolve(
+            "
+services:
+  base:
+    image: quay.io/podman/hello
+    command: [sleep, infinity]
+    environment:
+      A: base
+      B: base
+  web:
+    extends:
+      service: base
+    image: docker.io/library/nginx
+    command: [nginx]
+    environment:
+      B: web
+",
+        )
+        .expect("extends resolves");
+        let web = compose.services.0["web"].as_ref().unwrap();
+        assert_eq!(web.image.as_deref(), Some("docker.io/library/nginx"));
+        assert_eq!(
+            web.command,
+            Some(docker_compose_types::Command::Args(vec![String::from(
+                "nginx"
+            )]))
+        );
+        let docker_compose_types::Environment::KvPair(environment) = &web.environment else {
+            panic!("environment is a map");
+        };
+        assert_eq!(environment.len(), 2);
+        assert_eq!(
+            environment["A"],
+            Some(docker_compose_types::SingleValue::String(String::from(
+                "base"
+            )))
+        );
+        assert_eq!(
+            environment["B"],
+            Some(docker_compose_types::SingleValue::String(String::from(
+                "web"
+            )))
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        let error = resolve(
+            "
+services:
+  a:
+    extends:
+      service: b
+  b:
+    extends:
+      service: a
+",
+        )
+        .expect_err("extends is cyclic");
+        assert!(format!("{error:?}").contains("cyclic `extends`: a -> b -> a"));
+    }
+
+    #[test]
+    fn other_file_same_name() {
+        let dir = std::env::temp_dir().join(format!("podlet-extends-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("temp directory can be created");
+        fs::write(
+            dir.join("common.yaml"),
+            "
+services:
+  web:
+    image: docker.io/library/nginx
+    ports:
+      - 8080:80
+",
+        )
+        .expect("extended file is written");
+
+        let mut compose = serde_yaml::from_str(
+            "
+services:
+  web:
+    extends:
+      file: common.yaml
+      service: web
+    ports:
+      - 8443:443
+",
+        )
+        .expect("compose file is valid");
+        let result = resolve_extends(&mut compose, &dir);
+        fs::remove_dir_all(&dir).expect("temp directory can be removed");
+        result.expect("extends resolves");
+
+        let web = compose.services.0["web"].as_ref().unwrap();
+        assert_eq!(web.image.as_deref(), Some("docker.io/library/nginx"));
+        assert_eq!(
+            web.ports,
+            docker_compose_types::Ports::Short(vec![
+                String::from("8080:80"),
+                String::from("8443:443")
+            ])
+        );
+    }
+
+    const PROFILES: &str = "
+services:
+  web:
+    image: quay.io/podman/hello
+    depends_on:
+      - debug
+  debug:
+    image: quay.io/podman/hello
+    profiles: [debug]
+";
+
+    #[test]
+    fn profile_disabled() {
+        let mut compose = serde_yaml::from_str(PROFILES).expect("compose file is valid");
+        filter_profiles(&mut compose, &[]);
+        let services = compose.services.0;
+        assert_eq!(services.len(), 1);
+        assert_eq!(
+            services["web"].as_ref().unwrap().depends_on,
+            DependsOnOptions::Simple(Vec::new())
+        );
+    }
+
+    #[test]
+    fn profile_enabled() {
+        let mut compose = serde_yaml::from_str(PROFILES).expect("compose file is valid");
+        filter_profiles(&mut compose, &[String::from("debug")]);
+        let services = compose.services.0;
+        assert_eq!(services.len(), 2);
+        assert!(services["debug"].as_ref().unwrap().profiles.is_empty());
+        assert_eq!(
+            services["web"].as_ref().unwrap().depends_on,
+            DependsOnOptions::Simple(vec![String::from("debug")])
+        );
+    }
+}
@@ -2,7 +2,7 @@ mod podman;
 mod quadlet;
 pub mod security_opt;
 
-use std::mem;
+use std::{fmt::Write, mem};
 
 use clap::Args;
 use color_eyre::eyre::{self, Context};
@@ -10,6 +10,7 @@ use color_eyre::eyre::{self, Context};
 use self::{podman::PodmanArgs, quadlet::QuadletOptions, security_opt::SecurityOpt};
 use super::{image_to_name, ComposeService};
 
+#[allow(clippy::doc_markdown)]
 #[derive(Args, Default, Debug, Clone, PartialEq)]
 pub struct Container {
     #[command(flatten)]
@@ -46,14 +47,43 @@ impl TryFrom<ComposeService> for Container {
 
     fn try_from(mut value: ComposeService) -> Result<Self, Self::Error> {
         let service = &value.service;
+        let deploy = service.deploy.as_ref();
         let unsupported_options = [
-            ("deploy", service.deploy.is_some()),
+            (
+                "deploy.mode",
+                deploy.is_some_and(|deploy| deploy.mode.is_some()),
+            ),
+            (
+                "deploy.replicas",
+                deploy.is_some_and(|deploy| deploy.replicas.is_some()),
+            ),
+            (
+                "deploy.labels",
+                deploy.is_some_and(|deploy| !deploy.labels.is_empty()),
+            ),
+            (
+                "deploy.update_config",
+                deploy.is_some_and(|deploy| deploy.update_config.is_some()),
+            ),
+            (
+                "deploy.restart_policy",
+                deploy.is_some_and(|deploy| deploy.restart_policy.is_some()),
+            ),
+            (
+                "deploy.placement",
+                deploy.is_some_and(|deploy| deploy.placement.is_some()),
+            ),
+            (
+                "deploy.resources.reservations.cpus",
+                deploy
+                    .and_then(|deploy| deploy.resources.as_ref())
+                    .and_then(|resources| resources.reservations.as_ref())
+                    .is_some_and(|reservations| reservations.cpus.is_some()),
+            ),
             ("build", service.build_.is_some()),
-            ("profiles", !service.profiles.is_empty()),
             ("links", !service.links.is_empty()),
             ("net", service.net.is_some()),
             ("volumes_from", !service.volumes_from.is_empty()),
-            ("extends", !service.extends.is_empty()),
             ("scale", service.scale != 0),
         ];
         for (option, exists) in unsupported_options {
@@ -79,7 +109,7 @@ impl TryFrom<ComposeService> for Container {
             .collect::<Result<_, _>>()
             .wrap_err("invalid security option")?;
 
-        Ok(Self {
+        let container = Self {
             quadlet_options: (&mut value).try_into()?,
             podman_args: (&mut value.service).try_into()?,
             security_opt,
@@ -91,11 +121,16 @@ impl TryFrom<ComposeService> for Container {
                 .service
                 .command
                 .map(|command| match command {
-                    docker_compose_types::Command::Simple(s) => vec![s],
-                    docker_compose_types::Command::Args(args) => args,
+                    docker_compose_types::Command::Simple(s) => {
+                        shlex::split(&s).ok_or_else(|| eyre::eyre!("invalid command: `{s}`"))
+                    }
+                    docker_compose_types::Command::Args(args) => Ok(args),
                 })
+                .transpose()?
                 .unwrap_or_default(),
-        })
+        };
+        container.validate()?;
+        Ok(container)
     }
 }
 
@@ -112,7 +147,7 @@ impl From<Container> for crate::quadlet::Container {
             security_options.add_security_opt(security_opt);
         }
         for arg in security_options.podman_args {
-            podman_args += &format!(" --security-opt {arg}");
+            let _ = write!(podman_args, " --security-opt {arg}");
         }
 
         Self {
@@ -144,11 +179,27 @@ impl Container {
             .as_deref()
             .unwrap_or_else(|| image_to_name(&self.image))
     }
+
+    pub fn stop_signal(&self) -> Option<&str> {
+        self.podman_args.stop_signal.as_deref()
+    }
+
+    pub fn stop_timeout(&self) -> Option<u16> {
+        self.podman_args.stop_timeout
+    }
+
+    /// Returns an error if the container's options conflict
+    pub fn validate(&self) -> eyre::Result<()> {
+        self.quadlet_options.validate()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use clap::Parser;
+
     use super::*;
+    use crate::cli::{tests::quadlet, Cli};
 
     mod name {
         use super::*;
@@ -179,5 +230,791 @@ mod tests {
             };
             assert_eq!(sut.name(), "hello");
         }
+
+        #[test]
+        fn image_docker_hub() {
+            let sut = Container {
+                image: String::from("nginx:latest"),
+                ..Default::default()
+            };
+            assert_eq!(sut.name(), "nginx");
+        }
+
+        #[test]
+        fn image_with_digest() {
+            for image in [
+                "quay.io/podman/hello@sha256:1b0d76a7b5a8e3dea0d16c2b6e18b1bbcb53d4ea3a37ba4ab4e2c4e674a3c4a4",
+                "localhost:5000/podman/hello:latest@sha256:1b0d76a7b5a8e3dea0d16c2b6e18b1bbcb53d4ea3a37ba4ab4e2c4e674a3c4a4",
+            ] {
+                let sut = Container {
+                    image: String::from(image),
+                    ..Default::default()
+                };
+                assert_eq!(sut.name(), "hello");
+            }
+        }
+    }
+
+    #[test]
+    fn healthcheck() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--health-cmd",
+            "curl -f http://localhost/",
+            "--health-interval",
+            "30s",
+            "--health-timeout",
+            "5s",
+            "--health-retries",
+            "3",
+            "--health-start-period",
+            "10s",
+            "--health-on-failure",
+            "restart",
+            "quay.io/podman/hello",
+        ]);
+
+        for line in [
+            "HealthCmd=curl -f http://localhost/\n",
+            "HealthInterval=30s\n",
+            "HealthTimeout=5s\n",
+            "HealthRetries=3\n",
+            "HealthStartPeriod=10s\n",
+            "HealthOnFailure=restart\n",
+        ] {
+            assert!(sut.contains(line), "`{line}` not in:\n{sut}");
+        }
+    }
+
+    #[test]
+    fn secret() {
+        let sut = quadlet(&["podman", "run", "--secret", "foo", "quay.io/podman/hello"]);
+        assert!(sut.contains("Secret=foo\n"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--secret",
+            "mysecret,type=env,target=DB_PASS",
+            "--secret",
+            "cert,type=mount,uid=1000,gid=1000,mode=0400,target=/run/cert",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains(
+            "Secret=mysecret,type=env,target=DB_PASS\n\
+                Secret=cert,type=mount,uid=1000,gid=1000,mode=0400,target=/run/cert\n"
+        ));
+    }
+
+    #[test]
+    fn device() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--device",
+            "/dev/dri:/dev/dri:rwm",
+            "--device",
+            "/dev/fuse",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("AddDevice=/dev/dri:/dev/dri:rwm\nAddDevice=/dev/fuse\n"));
+    }
+
+    #[test]
+    fn tmpfs() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--tmpfs",
+            "/run",
+            "--tmpfs",
+            "/tmp:size=64m",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Tmpfs=/run\nTmpfs=/tmp:size=64m\n"));
+        assert!(!sut.contains("Volume="));
+        assert!(!sut.contains("VolatileTmp="));
+    }
+
+    #[test]
+    fn dns() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--dns",
+            "1.1.1.1",
+            "--dns",
+            "9.9.9.9",
+            "--dns-search",
+            "example.com",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("DNS=1.1.1.1\nDNS=9.9.9.9\nDNSSearch=example.com\n"));
+        assert!(!sut.contains("PodmanArgs="));
+    }
+
+    #[test]
+    fn sysctl() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--sysctl",
+            "net.ipv4.ip_forward=1",
+            "--sysctl",
+            "net.ipv6.conf.all.disable_ipv6=0",
+            "quay.io/podman/hello",
+        ]);
+        assert!(
+            sut.contains("Sysctl=net.ipv4.ip_forward=1\nSysctl=net.ipv6.conf.all.disable_ipv6=0\n")
+        );
+    }
+
+    #[test]
+    fn ulimit() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--ulimit",
+            "nofile=1024:2048",
+            "--ulimit",
+            "nproc=512",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Ulimit=nofile=1024:2048\nUlimit=nproc=512\n"));
+    }
+
+    #[test]
+    fn capabilities() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--cap-add",
+            "ALL",
+            "--cap-drop",
+            "NET_RAW",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("AddCapability=ALL\n"));
+        assert!(sut.contains("DropCapability=NET_RAW\n"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--cap-add",
+            "CAP_NET_ADMIN,CAP_SYS_TIME",
+            "--cap-add",
+            "CAP_CHOWN",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("AddCapability=CAP_NET_ADMIN CAP_SYS_TIME CAP_CHOWN\n"));
+    }
+
+    #[test]
+    fn security_opt() {
+        for (security_opt, line) in [
+            ("label=type:foo", "SecurityLabelType=foo"),
+            ("label=disable", "SecurityLabelDisable=true"),
+            ("no-new-privileges", "NoNewPrivileges=true"),
+            ("seccomp=profile.json", "SeccompProfile=profile.json"),
+            (
+                "apparmor=unconfined",
+                "PodmanArgs=--security-opt apparmor=unconfined",
+            ),
+        ] {
+            let sut = quadlet(&[
+                "podman",
+                "run",
+                "--security-opt",
+                security_opt,
+                "quay.io/podman/hello",
+            ]);
+            assert!(
+                sut.contains(&format!("{line}\n")),
+                "`{line}` not in:\n{sut}"
+            );
+        }
+    }
+
+    #[test]
+    fn user_group_add() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--user",
+            "1000:1000",
+            "--group-add",
+            "keep-groups",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("User=1000:1000\n"));
+        assert!(sut.contains("GroupAdd=keep-groups\n"));
+        assert!(!sut.contains("Group="));
+    }
+
+    #[test]
+    fn pull() {
+        let sut = quadlet(&["podman", "run", "--pull", "newer", "quay.io/podman/hello"]);
+        assert!(sut.contains("Pull=newer\n"));
+
+        let sut = quadlet(&["podman", "run", "quay.io/podman/hello"]);
+        assert!(!sut.contains("Pull="));
+    }
+
+    #[test]
+    fn pod() {
+        let sut = quadlet(&["podman", "run", "--pod", "mypod", "quay.io/podman/hello"]);
+        assert!(sut.contains("Pod=mypod.pod\n"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--pod",
+            "new:mypod",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Pod=mypod.pod\n"));
+        assert!(!sut.contains("PodmanArgs="));
+    }
+
+    #[test]
+    fn pod_file_from_pod_create() {
+        use clap::CommandFactory;
+
+        let command = Cli::command();
+        let run = command
+            .find_subcommand("podman")
+            .and_then(|podman| podman.find_subcommand("run"))
+            .expect("`podman run` exists");
+        let help = run
+            .get_arguments()
+            .find(|arg| arg.get_id() == "pod")
+            .and_then(clap::Arg::get_long_help)
+            .expect("`--pod` has help text")
+            .to_string();
+        assert!(help.contains("`podlet podman pod create --name NAME`"));
+
+        // the pod file `Pod=mypod.pod` refers to
+        let files = crate::cli::tests::podlet(&["podman", "pod", "create", "--name", "mypod"]);
+        assert_eq!(
+            files,
+            [(
+                String::from("mypod.pod"),
+                String::from("[Pod]\nPodName=mypod\n")
+            )]
+        );
+    }
+
+    #[test]
+    fn resource_limits() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--memory",
+            "512m",
+            "--memory-swap",
+            "1g",
+            "--cpus",
+            "1.5",
+            "--cpu-shares",
+            "512",
+            "--cpuset-cpus",
+            "0-3",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains(
+            "PodmanArgs=--cpu-shares 512 --cpus 1.5 --cpuset-cpus 0-3 --memory 512m \\\n    \
+            --memory-swap 1g\n"
+        ));
+        assert_eq!(sut.matches("PodmanArgs=").count(), 1);
+    }
+
+    #[test]
+    fn logging() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--log-driver",
+            "journald",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("LogDriver=journald\n"));
+        assert!(!sut.contains("LogOpt="));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--log-driver",
+            "journald",
+            "--log-opt",
+            "max-size=10mb",
+            "--log-opt",
+            "tag=web",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("LogDriver=journald\nLogOpt=max-size=10mb\nLogOpt=tag=web\n"));
+    }
+
+    #[test]
+    fn hostname() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--hostname",
+            "web01",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("HostName=web01\n"));
+        assert!(!sut.contains("--hostname"));
+
+        let sut = quadlet(&["podman", "run", "quay.io/podman/hello"]);
+        assert!(!sut.contains("HostName="));
+    }
+
+    #[test]
+    fn static_ip() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--ip",
+            "10.88.64.128",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("IP=10.88.64.128\n"));
+        assert!(!sut.contains("IP6="));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--ip6",
+            "fd46:db93:aa76:ac37::10",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("IP6=fd46:db93:aa76:ac37::10\n"));
+        assert!(!sut.contains("IP="));
+
+        let sut = quadlet(&["podman", "run", "quay.io/podman/hello"]);
+        assert!(!sut.contains("IP"));
+    }
+
+    #[test]
+    fn invalid_static_ip() {
+        assert!(Cli::try_parse_from([
+            "podlet",
+            "podman",
+            "run",
+            "--ip",
+            "10.88.64",
+            "quay.io/podman/hello"
+        ])
+        .is_err());
+        assert!(Cli::try_parse_from([
+            "podlet",
+            "podman",
+            "run",
+            "--ip6",
+            "10.88.64.128",
+            "quay.io/podman/hello"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn mac_address() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--mac-address",
+            "92:d0:c6:0a:29:33",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("MacAddress=92:d0:c6:0a:29:33\n"));
+        assert!(!sut.contains("--mac-address"));
+    }
+
+    #[test]
+    fn label() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--label",
+            "io.containers.autoupdate=registry",
+            "-l",
+            "internal",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Label=io.containers.autoupdate=registry\nLabel=internal\n"));
+    }
+
+    #[test]
+    fn annotation() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--annotation",
+            "io.podman.annotations.init=true",
+            "--annotation",
+            "org.example.owner=ops",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains(
+            "Annotation=io.podman.annotations.init=true\nAnnotation=org.example.owner=ops\n"
+        ));
+        assert!(!sut.contains("Label="));
+    }
+
+    #[test]
+    fn env_file() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--env-file",
+            "./app.env",
+            "--env-file",
+            "/etc/app/secrets.env",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("EnvironmentFile=./app.env\nEnvironmentFile=/etc/app/secrets.env\n"));
+    }
+
+    #[test]
+    fn entrypoint() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--entrypoint",
+            "/bin/sh",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Entrypoint=/bin/sh\n"));
+        assert!(!sut.contains("--entrypoint"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--entrypoint",
+            r#"["/bin/sh","-c"]"#,
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Entrypoint=[\"/bin/sh\",\"-c\"]\n"));
+    }
+
+    #[test]
+    fn workdir() {
+        let sut = quadlet(&["podman", "run", "--workdir", "/app", "quay.io/podman/hello"]);
+        assert!(sut.contains("WorkingDir=/app\n"));
+        assert!(!sut.contains("--workdir"));
+
+        let sut = quadlet(&["podman", "run", "quay.io/podman/hello"]);
+        assert!(!sut.contains("WorkingDir="));
+    }
+
+    #[test]
+    fn read_only() {
+        let sut = quadlet(&["podman", "run", "quay.io/podman/hello"]);
+        assert!(!sut.contains("ReadOnly"));
+
+        let sut = quadlet(&["podman", "run", "--read-only", "quay.io/podman/hello"]);
+        assert!(sut.contains("ReadOnly=true\n"));
+        assert!(!sut.contains("ReadOnlyTmpfs="));
+
+        let sut = quadlet(&["podman", "run", "--read-only-tmpfs", "quay.io/podman/hello"]);
+        assert!(sut.contains("ReadOnlyTmpfs=true\n"));
+        assert!(!sut.contains("ReadOnly=true"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--read-only",
+            "--read-only-tmpfs",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("ReadOnly=true\nReadOnlyTmpfs=true\n"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--read-only",
+            "--read-only-tmpfs=false",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("ReadOnly=true\nReadOnlyTmpfs=false\n"));
+        assert!(!sut.contains("PodmanArgs="));
+    }
+
+    #[test]
+    fn mount() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--mount",
+            "type=bind,src=/data,dst=/data,ro",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Mount=type=bind,src=/data,dst=/data,ro\n"));
+        assert!(!sut.contains("Volume="));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--mount",
+            "type=tmpfs,tmpfs-size=512M,destination=/cache",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Mount=type=tmpfs,tmpfs-size=512M,destination=/cache\n"));
+        assert!(!sut.contains("Tmpfs="));
+    }
+
+    #[test]
+    fn volume() {
+        let sut = quadlet(&["podman", "run", "-v", "myvol:/data", "quay.io/podman/hello"]);
+        assert!(sut.contains("Volume=myvol.volume:/data\n"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "-v",
+            "myvol.volume:/data:z",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Volume=myvol.volume:/data:z\n"));
+
+        let sut = quadlet(&["podman", "run", "-v", "/host:/cont", "quay.io/podman/hello"]);
+        assert!(sut.contains("Volume=/host:/cont\n"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "-v",
+            "./config:/etc/app:ro",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Volume=./config:/etc/app:ro\n"));
+
+        let sut = quadlet(&["podman", "run", "-v", "/data", "quay.io/podman/hello"]);
+        assert!(sut.contains("Volume=/data\n"));
+    }
+
+    #[test]
+    fn volume_host_path_not_named() {
+        for volume in ["%h/data:/data", "data/sub:/x", "C:x:/y"] {
+            let sut = quadlet(&["podman", "run", "-v", volume, "quay.io/podman/hello"]);
+            assert!(
+                !sut.contains(".volume"),
+                "`{volume}` is not a named volume: {sut}"
+            );
+        }
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "-v",
+            "%h/data:/data",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Volume=%h/data:/data\n"));
+    }
+
+    #[test]
+    fn expose() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--expose",
+            "8080",
+            "--expose",
+            "9090/tcp",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("ExposeHostPort=8080\nExposeHostPort=9090/tcp\n"));
+        assert!(!sut.contains("PublishPort="));
+    }
+
+    #[test]
+    fn add_host() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--add-host",
+            "db.local:10.0.0.5",
+            "--add-host",
+            "host.docker.internal:host-gateway",
+            "quay.io/podman/hello",
+        ]);
+        assert!(
+            sut.contains("AddHost=db.local:10.0.0.5\nAddHost=host.docker.internal:host-gateway\n")
+        );
+        assert!(!sut.contains("--add-host"));
+    }
+
+    #[test]
+    fn shm_size() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--shm-size",
+            "256m",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("ShmSize=256m\n"));
+        assert!(!sut.contains("--shm-size"));
+
+        let sut = quadlet(&["podman", "run", "quay.io/podman/hello"]);
+        assert!(!sut.contains("ShmSize="));
+    }
+
+    #[test]
+    fn init() {
+        let sut = quadlet(&["podman", "run", "--init", "quay.io/podman/hello"]);
+        assert!(sut.contains("RunInit=true\n"));
+
+        let sut = quadlet(&["podman", "run", "quay.io/podman/hello"]);
+        assert!(!sut.contains("RunInit="));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--init",
+            "--init-path",
+            "/usr/libexec/podman/catatonit",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("RunInit=true\n"));
+        assert!(sut.contains("PodmanArgs=--init-path /usr/libexec/podman/catatonit\n"));
+    }
+
+    #[test]
+    fn pids_limit() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--pids-limit",
+            "512",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("PidsLimit=512\n"));
+        assert!(!sut.contains("--pids-limit"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--pids-limit",
+            "-1",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("PidsLimit=-1\n"));
+
+        let sut = quadlet(&["podman", "run", "quay.io/podman/hello"]);
+        assert!(!sut.contains("PidsLimit="));
+    }
+
+    #[test]
+    fn id_maps() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--uidmap",
+            "0:100000:65536",
+            "--gidmap",
+            "0:200000:65536",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("UIDMap=0:100000:65536\n"));
+        assert!(sut.contains("GIDMap=0:200000:65536\n"));
+        assert!(!sut.contains("PodmanArgs="));
+    }
+
+    #[test]
+    fn namespaces() {
+        // quadlet only has a dedicated option for the user namespace, `UserNS=`
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--userns",
+            "keep-id",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("UserNS=keep-id\n"));
+
+        for (namespace, mode) in [("--pid", "host"), ("--ipc", "shareable"), ("--uts", "host")] {
+            let sut = quadlet(&["podman", "run", namespace, mode, "quay.io/podman/hello"]);
+            assert!(sut.contains(&format!("PodmanArgs={namespace} {mode}\n")));
+        }
+    }
+
+    #[test]
+    fn podman_args() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--name",
+            "web",
+            "--cpu-rt-runtime=950000",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("ContainerName=web\n"));
+        assert!(sut.contains("PodmanArgs=--cpu-rt-runtime 950000\n"));
+        assert!(!sut.contains("--name"));
+    }
+
+    #[test]
+    fn gpus() {
+        let sut = quadlet(&["podman", "run", "--gpus", "all", "quay.io/podman/hello"]);
+        assert!(sut.contains("PodmanArgs=--gpus all\n"));
+
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--gpus",
+            "device=0,1",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("PodmanArgs=--gpus \"device=0,1\"\n"));
+    }
+
+    #[test]
+    fn escaped_environment_and_exec() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--env",
+            "GREETING=hello world",
+            "--env",
+            "LOAD=50%",
+            "quay.io/podman/hello",
+            "printf",
+            "%s",
+        ]);
+        assert!(sut.contains("Environment=\"GREETING=hello world\" LOAD=50%%\n"));
+        assert!(sut.contains("Exec=printf \"%%s\"\n"));
+    }
+
+    #[test]
+    fn environment_with_newline() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--env",
+            "A=line1\nline2",
+            "--env",
+            "B=1",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("Environment=\"A=line1\\nline2\" B=1\n"));
+    }
+
+    #[test]
+    fn escaped_health_cmd_and_label() {
+        let sut = quadlet(&[
+            "podman",
+            "run",
+            "--health-cmd",
+            "date +%s",
+            "--label",
+            "path=C:\\",
+            "quay.io/podman/hello",
+        ]);
+        assert!(sut.contains("HealthCmd=date +%%s\n"));
+        assert!(sut.contains("Label=path=C:\\\\\n"));
     }
 }
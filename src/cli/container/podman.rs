@@ -1,6 +1,5 @@
 use std::{
     fmt::{self, Display, Formatter},
-    mem,
     path::{Path, PathBuf},
 };
 
@@ -10,12 +9,6 @@ use color_eyre::eyre::Context;
 #[allow(clippy::struct_excessive_bools, clippy::module_name_repetitions)]
 #[derive(Args, Debug, Clone, PartialEq)]
 pub struct PodmanArgs {
-    /// Add a custom host-to-IP mapping
-    ///
-    /// Can be specified multiple times
-    #[arg(long, value_name = "HOST:IP")]
-    add_host: Vec<String>,
-
     /// Override the architecture of the image to be pulled
     ///
     /// Defaults to hosts architecture
@@ -150,48 +143,23 @@ pub struct PodmanArgs {
     #[arg(long)]
     disable_content_trust: bool,
 
-    /// Set custom DNS servers
-    #[arg(long, value_name = "IP_ADDRESS")]
-    dns: Vec<String>,
-
-    /// Set custom DNS options
-    #[arg(long, value_name = "OPTION")]
-    dns_option: Option<String>,
-
-    /// Set custom DNS search domains
-    #[arg(long, value_name = "DOMAIN")]
-    dns_search: Option<String>,
-
-    /// Override the default entrypoint of the image
-    #[arg(long, value_name = "\"COMMAND\" | '[\"COMMAND\", \"ARG1\", ...]'")]
-    entrypoint: Option<String>,
-
     /// Preprocess default environment variables for the container
     ///
     /// Can be specified multiple times
     #[arg(long, value_name = "ENV")]
     env_merge: Vec<String>,
 
-    /// Run the container in a new user namespace using the supplied GID mapping
+    /// GPU devices to add to the container, e.g. `all` or `device=0,1`
     ///
-    /// Can be specified multiple times
-    #[arg(long, value_name = "CONTAINER_GID:HOST_GID:AMOUNT")]
-    gidmap: Vec<String>,
-
-    /// Assign additional groups to the primary user running within the container process
-    ///
-    /// Can be specified multiple times
-    #[arg(long, value_name = "GROUP")]
-    group_add: Vec<String>,
+    /// Quadlet has no dedicated option for this,
+    /// use "--device nvidia.com/gpu=all" to add GPUs with CDI instead
+    #[arg(long, value_name = "ENTRY")]
+    gpus: Option<String>,
 
     /// Customize the entry that is written to the /etc/group file within the container
     #[arg(long, value_name = "ENTRY")]
     group_entry: Option<String>,
 
-    /// Set container hostname
-    #[arg(long, value_name = "NAME")]
-    hostname: Option<String>,
-
     /// Add a user account to /etc/passwd from the host to the container
     #[arg(long, value_name = "NAME")]
     hostuser: Vec<String>,
@@ -213,7 +181,7 @@ pub struct PodmanArgs {
     interactive: bool,
 
     /// Set the IPC namespace mode for the container
-    #[arg(long)]
+    #[arg(long, value_name = "MODE")]
     ipc: Option<String>,
 
     /// Read in a line-delimited file of labels
@@ -224,16 +192,6 @@ pub struct PodmanArgs {
     #[arg(long, value_name = "IP")]
     link_local_ip: Option<String>,
 
-    /// Logging driver specific options
-    ///
-    /// Can be specified multiple times
-    #[arg(long, value_name = "NAME=VALUE")]
-    log_opt: Vec<String>,
-
-    /// Container network interface MAC address
-    #[arg(long, value_name = "ADDRESS")]
-    mac_address: Option<String>,
-
     /// Memory limit
     #[arg(short, long, value_name = "NUMBER[UNIT]")]
     memory: Option<String>,
@@ -294,18 +252,10 @@ pub struct PodmanArgs {
     #[arg(long, value_name = "PATH")]
     pidfile: Option<PathBuf>,
 
-    /// Tune the container’s pids limit
-    #[arg(long, value_name = "LIMIT")]
-    pids_limit: Option<i16>,
-
     /// Specify the platform for selecting the image
     #[arg(long, value_name = "OS/ARCH")]
     platform: Option<String>,
 
-    /// Run the container in an existing pod
-    #[arg(long, value_name = "NAME")]
-    pod: Option<String>,
-
     /// Read the pod ID from the file
     #[arg(long, value_name = "FILE")]
     pod_id_file: Option<PathBuf>,
@@ -322,18 +272,10 @@ pub struct PodmanArgs {
     #[arg(short = 'P', long)]
     publish_all: bool,
 
-    /// Pull image policy
-    #[arg(long, value_name = "POLICY")]
-    pull: Option<String>,
-
     /// Suppress output information when pulling images
     #[arg(short, long)]
     quiet: bool,
 
-    /// When running containers in read-only mode mount a read-write tmpfs on /run, /tmp and /var/tmp
-    #[arg(long, action = ArgAction::Set, default_value_t = true)]
-    read_only_tmpfs: bool,
-
     /// If a container with the same name exists, replace it
     ///
     /// Automatically set by quadlet
@@ -358,10 +300,6 @@ pub struct PodmanArgs {
     #[arg(long, value_name = "POLICY")]
     seccomp_policy: Option<String>,
 
-    /// Size of /dev/shm
-    #[arg(long, value_name = "NUMBER[UNIT]")]
-    shm_size: Option<String>,
-
     /// Size of systemd-specific tmpfs mounts: /run, /run/lock, /var/log/journal, and /tmp
     #[arg(long, value_name = "NUMBER[UNIT]")]
     shm_size_systemd: Option<String>,
@@ -371,14 +309,18 @@ pub struct PodmanArgs {
     sig_proxy: bool,
 
     /// Signal to stop a container
+    ///
+    /// Also converts to "KillSignal=SIGNAL" in the [Service] section
     #[arg(long, value_name = "SIGNAL")]
-    stop_signal: Option<String>,
+    pub stop_signal: Option<String>,
 
     /// Timeout to stop a container
     ///
     /// Default is 10
+    ///
+    /// Also converts to "TimeoutStopSec=SECONDS" in the [Service] section
     #[arg(long, value_name = "SECONDS")]
-    stop_timeout: Option<u16>,
+    pub stop_timeout: Option<u16>,
 
     /// Name of range listed in /etc/subgid for use in user namespace
     #[arg(long, value_name = "NAME")]
@@ -388,12 +330,6 @@ pub struct PodmanArgs {
     #[arg(long, value_name = "NAME")]
     subuidname: Option<String>,
 
-    /// Configure namespaced kernel parameters at runtime
-    ///
-    /// Can be specified multiple times
-    #[arg(long, value_name = "NAME=VALUE")]
-    sysctl: Vec<String>,
-
     /// Run container in systemd mode
     ///
     /// Default is true
@@ -412,22 +348,14 @@ pub struct PodmanArgs {
     #[arg(short, long)]
     tty: bool,
 
-    /// Run the container in a new user namespace using the supplied UID mapping
-    ///
-    /// Can be specified multiple times
-    #[arg(long, value_name = "CONTAINER_UID:FROM_UID:AMOUNT")]
-    uidmap: Vec<String>,
-
-    /// Ulimit options
-    ///
-    /// Can be specified multiple times
-    #[arg(long, value_name = "OPTION")]
-    ulimit: Vec<String>,
-
     /// Set the umask inside the container
     #[arg(long)]
     umask: Option<String>,
 
+    /// Set the UTS namespace mode for the container
+    #[arg(long, value_name = "MODE")]
+    uts: Option<String>,
+
     /// Set variant to use instead of the default architecture variant of the container image
     #[arg(long)]
     variant: Option<String>,
@@ -437,16 +365,11 @@ pub struct PodmanArgs {
     /// Can be specified multiple times
     #[arg(long, value_name = "CONTAINER[:OPTIONS]")]
     volumes_from: Vec<String>,
-
-    /// Working directory inside the container
-    #[arg(short, long, value_name = "DIR")]
-    workdir: Option<PathBuf>,
 }
 
 impl Default for PodmanArgs {
     fn default() -> Self {
         Self {
-            add_host: Vec::new(),
             arch: None,
             attach: Vec::new(),
             authfile: None,
@@ -476,15 +399,9 @@ impl Default for PodmanArgs {
             device_write_bps: Vec::new(),
             device_write_iops: Vec::new(),
             disable_content_trust: false,
-            dns: Vec::new(),
-            dns_option: None,
-            dns_search: None,
-            entrypoint: None,
             env_merge: Vec::new(),
-            gidmap: Vec::new(),
-            group_add: Vec::new(),
+            gpus: None,
             group_entry: None,
-            hostname: None,
             hostuser: Vec::new(),
             http_proxy: true,
             image_volume: None,
@@ -493,8 +410,6 @@ impl Default for PodmanArgs {
             ipc: None,
             label_file: None,
             link_local_ip: None,
-            log_opt: Vec::new(),
-            mac_address: None,
             memory: None,
             memory_reservation: None,
             memory_swap: None,
@@ -510,39 +425,31 @@ impl Default for PodmanArgs {
             personality: None,
             pid: None,
             pidfile: None,
-            pids_limit: None,
             platform: None,
-            pod: None,
             pod_id_file: None,
             preserve_fds: None,
             privileged: false,
             publish_all: false,
-            pull: None,
             quiet: false,
-            read_only_tmpfs: true,
             replace: false,
             requires: None,
             rm: false,
             rmi: false,
             seccomp_policy: None,
-            shm_size: None,
             shm_size_systemd: None,
             sig_proxy: true,
             stop_signal: None,
             stop_timeout: None,
             subgidname: None,
             subuidname: None,
-            sysctl: Vec::new(),
             systemd: None,
             timeout: None,
             tls_verify: None,
             tty: false,
-            uidmap: Vec::new(),
-            ulimit: Vec::new(),
             umask: None,
+            uts: None,
             variant: None,
             volumes_from: Vec::new(),
-            workdir: None,
         }
     }
 }
@@ -550,8 +457,7 @@ impl Default for PodmanArgs {
 impl PodmanArgs {
     /// The total resulting number of arguments
     fn args_len(&self) -> usize {
-        (self.add_host.len()
-            + self.arch.iter().len()
+        (self.arch.iter().len()
             + self.attach.len()
             + self.authfile.iter().len()
             + self.blkio_weight.iter().len()
@@ -578,15 +484,9 @@ impl PodmanArgs {
             + self.device_read_iops.len()
             + self.device_write_bps.len()
             + self.device_write_iops.len()
-            + self.dns.len()
-            + self.dns_option.iter().len()
-            + self.dns_search.iter().len()
-            + self.entrypoint.iter().len()
             + self.env_merge.len()
-            + self.gidmap.len()
-            + self.group_add.len()
+            + self.gpus.iter().len()
             + self.group_entry.iter().len()
-            + self.hostname.iter().len()
             + self.hostuser.len()
             + usize::from(!self.http_proxy)
             + self.image_volume.iter().len()
@@ -594,8 +494,6 @@ impl PodmanArgs {
             + self.ipc.iter().len()
             + self.label_file.iter().len()
             + self.link_local_ip.iter().len()
-            + self.log_opt.len()
-            + self.mac_address.iter().len()
             + self.memory.iter().len()
             + self.memory_reservation.iter().len()
             + self.memory_swap.iter().len()
@@ -607,32 +505,24 @@ impl PodmanArgs {
             + self.personality.iter().len()
             + self.pid.iter().len()
             + self.pidfile.iter().len()
-            + self.pids_limit.iter().len()
             + self.platform.iter().len()
-            + self.pod.iter().len()
             + self.pod_id_file.iter().len()
             + self.preserve_fds.iter().len()
-            + self.pull.iter().len()
-            + usize::from(!self.read_only_tmpfs)
             + self.requires.iter().len()
             + self.seccomp_policy.iter().len()
-            + self.shm_size.iter().len()
             + self.shm_size_systemd.iter().len()
             + usize::from(!self.sig_proxy)
             + self.stop_signal.iter().len()
             + self.stop_timeout.iter().len()
             + self.subgidname.iter().len()
             + self.subuidname.iter().len()
-            + self.sysctl.len()
             + self.systemd.iter().len()
             + self.timeout.iter().len()
             + self.tls_verify.iter().len()
-            + self.uidmap.len()
-            + self.ulimit.len()
             + self.umask.iter().len()
+            + self.uts.iter().len()
             + self.variant.iter().len()
-            + self.volumes_from.len()
-            + self.workdir.iter().len())
+            + self.volumes_from.len())
             * 2
             + usize::from(self.interactive)
             + usize::from(self.no_healthcheck)
@@ -660,8 +550,6 @@ impl Display for PodmanArgs {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let mut args = Vec::with_capacity(self.args_len());
 
-        extend_args(&mut args, "--add-host", &self.add_host);
-
         extend_args(&mut args, "--arch", &self.arch);
 
         extend_args(&mut args, "--attach", &self.attach);
@@ -731,24 +619,12 @@ impl Display for PodmanArgs {
 
         extend_args(&mut args, "--device-write-iops", &self.device_write_iops);
 
-        extend_args(&mut args, "--dns", &self.dns);
-
-        extend_args(&mut args, "--dns-option", &self.dns_option);
-
-        extend_args(&mut args, "--dns-search", &self.dns_search);
-
-        extend_args(&mut args, "--entrypoint", &self.entrypoint);
-
         extend_args(&mut args, "--env-merge", &self.env_merge);
 
-        extend_args(&mut args, "--gidmap", &self.gidmap);
-
-        extend_args(&mut args, "--group-add", &self.group_add);
+        extend_args(&mut args, "--gpus", &self.gpus);
 
         extend_args(&mut args, "--group-entry", &self.group_entry);
 
-        extend_args(&mut args, "--hostname", &self.hostname);
-
         extend_args(&mut args, "--hostuser", &self.hostuser);
 
         if !self.http_proxy {
@@ -771,10 +647,6 @@ impl Display for PodmanArgs {
 
         extend_args(&mut args, "--link-local-ip", &self.link_local_ip);
 
-        extend_args(&mut args, "--log-opt", &self.log_opt);
-
-        extend_args(&mut args, "--mac-address", &self.mac_address);
-
         extend_args(&mut args, "--memory", &self.memory);
 
         extend_args(&mut args, "--memory-reservation", &self.memory_reservation);
@@ -818,13 +690,8 @@ impl Display for PodmanArgs {
         let pidfile = self.pidfile.as_deref().map(Path::to_string_lossy);
         extend_args(&mut args, "--pidfile", &pidfile);
 
-        let pids_limit = self.pids_limit.map(|limit| limit.to_string());
-        extend_args(&mut args, "--pids-limit", &pids_limit);
-
         extend_args(&mut args, "--platform", &self.platform);
 
-        extend_args(&mut args, "--pod", &self.pod);
-
         let pod_id_file = self.pod_id_file.as_deref().map(Path::to_string_lossy);
         extend_args(&mut args, "--pod-id-file", &pod_id_file);
 
@@ -839,16 +706,10 @@ impl Display for PodmanArgs {
             args.push("--publish-all");
         }
 
-        extend_args(&mut args, "--pull", &self.pull);
-
         if self.quiet {
             args.push("--quiet");
         }
 
-        if !self.read_only_tmpfs {
-            args.extend(["--read-only-tmpfs", "false"]);
-        }
-
         extend_args(&mut args, "--requires", &self.requires);
 
         if self.rmi {
@@ -857,8 +718,6 @@ impl Display for PodmanArgs {
 
         extend_args(&mut args, "--seccomp-policy", &self.seccomp_policy);
 
-        extend_args(&mut args, "--shm-size", &self.shm_size);
-
         extend_args(&mut args, "--shm-size-systemd", &self.shm_size_systemd);
 
         if !self.sig_proxy {
@@ -874,8 +733,6 @@ impl Display for PodmanArgs {
 
         extend_args(&mut args, "--subuidname", &self.subuidname);
 
-        extend_args(&mut args, "--sysctl", &self.sysctl);
-
         extend_args(&mut args, "--systemd", &self.systemd);
 
         let timeout = self.timeout.map(|timeout| timeout.to_string());
@@ -888,16 +745,11 @@ impl Display for PodmanArgs {
             args.push("--tty");
         }
 
-        extend_args(&mut args, "--uidmap", &self.uidmap);
-
-        extend_args(&mut args, "--ulimit", &self.ulimit);
-
         extend_args(&mut args, "--umask", &self.umask);
 
-        extend_args(&mut args, "--volumes-from", &self.volumes_from);
+        extend_args(&mut args, "--uts", &self.uts);
 
-        let workdir = self.workdir.as_deref().map(Path::to_string_lossy);
-        extend_args(&mut args, "--workdir", &workdir);
+        extend_args(&mut args, "--volumes-from", &self.volumes_from);
 
         debug_assert_eq!(args.len(), self.args_len());
 
@@ -917,25 +769,13 @@ impl TryFrom<&mut docker_compose_types::Service> for PodmanArgs {
     type Error = color_eyre::Report;
 
     fn try_from(value: &mut docker_compose_types::Service) -> Result<Self, Self::Error> {
-        let ulimit = mem::take(&mut value.ulimits)
-            .0
-            .into_iter()
-            .map(|(kind, ulimit)| match ulimit {
-                docker_compose_types::Ulimit::Single(soft) => format!("{kind}={soft}"),
-                docker_compose_types::Ulimit::SoftHard { soft, hard } => {
-                    if hard == 0 {
-                        format!("{kind}={soft}")
-                    } else {
-                        format!("{kind}={soft}:{hard}")
-                    }
-                }
-            })
-            .collect();
-
-        let entrypoint = value.entrypoint.take().map(|entrypoint| match entrypoint {
-            docker_compose_types::Entrypoint::Simple(entrypoint) => entrypoint,
-            docker_compose_types::Entrypoint::List(list) => format!("{list:?}"),
-        });
+        let resources = value
+            .deploy
+            .take()
+            .and_then(|deploy| deploy.resources)
+            .unwrap_or_default();
+        let limits = resources.limits.unwrap_or_default();
+        let reservations = resources.reservations.unwrap_or_default();
 
         let stop_timeout = value
             .stop_grace_period
@@ -951,45 +791,21 @@ impl TryFrom<&mut docker_compose_types::Service> for PodmanArgs {
             })
             .transpose()?;
 
-        let log_opt = value
-            .logging
-            .as_mut()
-            .and_then(|logging| logging.options.take())
-            .unwrap_or_default()
-            .into_iter()
-            .map(|(key, value)| format!("{key}={value}"))
-            .collect();
-
-        let sysctl = match mem::take(&mut value.sysctls) {
-            docker_compose_types::SysCtls::List(vec) => vec,
-            docker_compose_types::SysCtls::Map(map) => map
-                .into_iter()
-                .map(|(key, value)| {
-                    let value = value
-                        .as_ref()
-                        .map_or_else(|| String::from("null"), ToString::to_string);
-                    format!("{key}={value}")
-                })
-                .collect(),
-        };
-
         Ok(Self {
-            hostname: value.hostname.take(),
+            cpus: limits
+                .cpus
+                .map(|cpus| cpus.parse())
+                .transpose()
+                .wrap_err("invalid deploy.resources.limits.cpus")?,
+            memory: limits.memory,
+            memory_reservation: reservations.memory,
             privileged: value.privileged,
             pid: value.pid.take(),
-            ulimit,
-            entrypoint,
             stop_signal: value.stop_signal.take(),
             stop_timeout,
-            dns: mem::take(&mut value.dns),
             ipc: value.ipc.take(),
-            workdir: value.working_dir.take().map(Into::into),
             interactive: value.stdin_open,
-            shm_size: value.shm_size.take(),
-            log_opt,
-            add_host: mem::take(&mut value.extra_hosts),
             tty: value.tty,
-            sysctl,
             ..Self::default()
         })
     }
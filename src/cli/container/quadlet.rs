@@ -4,22 +4,22 @@ use std::{
     path::PathBuf,
 };
 
-use clap::{Args, ValueEnum};
+use clap::{builder::TypedValueParser, Args, ValueEnum};
 use color_eyre::eyre::{self, Context};
 use docker_compose_types::MapOrEmpty;
 
 use super::unsupported_option;
 use crate::cli::ComposeService;
 
-#[allow(clippy::module_name_repetitions)]
+#[allow(clippy::module_name_repetitions, clippy::doc_markdown)]
 #[derive(Args, Default, Debug, Clone, PartialEq)]
 pub struct QuadletOptions {
     /// Add Linux capabilities
     ///
-    /// Converts to "AddCapability=CAPABILITY"
+    /// Converts to "AddCapability=CAPABILITY[ ...]"
     ///
     /// Can be specified multiple times
-    #[arg(long, value_name = "CAPABILITY")]
+    #[arg(long, value_name = "CAPABILITY[,...]", value_delimiter = ',')]
     cap_add: Vec<String>,
 
     /// Add a device node from the host into the container
@@ -30,6 +30,14 @@ pub struct QuadletOptions {
     #[arg(long, value_name = "HOST-DEVICE[:CONTAINER-DEVICE][:PERMISSIONS]")]
     device: Vec<String>,
 
+    /// Add a custom host-to-IP mapping
+    ///
+    /// Converts to "AddHost=HOST:IP"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "HOST:IP")]
+    add_host: Vec<String>,
+
     /// Add an annotation to the container
     ///
     /// Converts to "Annotation=KEY=VALUE"
@@ -46,16 +54,46 @@ pub struct QuadletOptions {
     #[arg(long)]
     pub name: Option<String>,
 
+    /// Set custom DNS servers
+    ///
+    /// Converts to "DNS=IP_ADDRESS"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "IP_ADDRESS")]
+    dns: Vec<String>,
+
+    /// Set custom DNS options
+    ///
+    /// Converts to "DNSOption=OPTION"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "OPTION")]
+    dns_option: Vec<String>,
+
+    /// Set custom DNS search domains
+    ///
+    /// Converts to "DNSSearch=DOMAIN"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "DOMAIN")]
+    dns_search: Vec<String>,
+
     /// Drop Linux capability from the default podman capability set
     ///
     /// If unspecified, the default is `all`
     ///
-    /// Converts to "DropCapability=CAPABILITY"
+    /// Converts to "DropCapability=CAPABILITY[ ...]"
     ///
     /// Can be specified multiple times
-    #[arg(long, value_name = "CAPABILITY")]
+    #[arg(long, value_name = "CAPABILITY[,...]", value_delimiter = ',')]
     cap_drop: Vec<String>,
 
+    /// Override the default entrypoint of the image
+    ///
+    /// Converts to "Entrypoint=ENTRYPOINT"
+    #[arg(long, value_name = "\"COMMAND\" | '[\"COMMAND\", \"ARG1\", ...]'")]
+    entrypoint: Option<String>,
+
     /// Set environment variables in the container
     ///
     /// Converts to "Environment=ENV"
@@ -86,6 +124,22 @@ pub struct QuadletOptions {
     #[arg(long, value_name = "PORT")]
     expose: Vec<String>,
 
+    /// Run the container in a new user namespace using the supplied GID mapping
+    ///
+    /// Converts to "GIDMap=CONTAINER_GID:HOST_GID:AMOUNT"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "CONTAINER_GID:HOST_GID:AMOUNT")]
+    gidmap: Vec<String>,
+
+    /// Assign additional groups to the primary user running within the container process
+    ///
+    /// Converts to "GroupAdd=GROUP"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "GROUP")]
+    group_add: Vec<String>,
+
     /// Set or alter a healthcheck command for the container
     ///
     /// Converts to "HealthCmd=COMMAND"
@@ -152,6 +206,12 @@ pub struct QuadletOptions {
     #[arg(long, value_name = "TIMEOUT")]
     health_timeout: Option<String>,
 
+    /// Set the container's hostname
+    ///
+    /// Converts to "HostName=NAME"
+    #[arg(long, value_name = "NAME")]
+    hostname: Option<String>,
+
     /// Specify a static IPv4 address for the container
     ///
     /// Converts to "IP=IPV4"
@@ -178,6 +238,20 @@ pub struct QuadletOptions {
     #[arg(long, value_name = "DRIVER")]
     log_driver: Option<String>,
 
+    /// Logging driver specific options
+    ///
+    /// Converts to "LogOpt=NAME=VALUE"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "NAME=VALUE")]
+    log_opt: Vec<String>,
+
+    /// Set a static MAC address for the container
+    ///
+    /// Converts to "MacAddress=ADDRESS"
+    #[arg(long, value_name = "ADDRESS")]
+    mac_address: Option<String>,
+
     /// Attach a filesystem mount to the container
     ///
     /// Converts to "Mount=MOUNT"
@@ -206,6 +280,27 @@ pub struct QuadletOptions {
     #[arg(long, value_name = "PATH[:OPTIONS]")]
     rootfs: Option<String>,
 
+    /// Tune the container's pids limit
+    ///
+    /// Set to `-1` for unlimited pids
+    ///
+    /// Converts to "PidsLimit=LIMIT"
+    #[arg(long, value_name = "LIMIT", allow_negative_numbers = true)]
+    pids_limit: Option<i64>,
+
+    /// Run the container in a quadlet managed pod
+    ///
+    /// Converts to "Pod=NAME.pod"
+    ///
+    /// A `new:` prefix is removed from the name.
+    ///
+    /// The `NAME.pod` file is not generated, quadlet requires it to exist.
+    /// Create it with `podlet podman pod create --name NAME`.
+    ///
+    /// Conflicts with --network and --publish, they must be set on the pod instead
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["network", "publish"])]
+    pod: Option<String>,
+
     /// Publish a container's port, or a range of ports, to the host
     ///
     /// Converts to "PublishPort=PORT"
@@ -218,12 +313,33 @@ pub struct QuadletOptions {
     )]
     publish: Vec<String>,
 
+    /// The image pull policy
+    ///
+    /// Accepted values are `always`, `missing`, `never`, and `newer`
+    ///
+    /// Converts to "Pull=POLICY"
+    #[arg(long, value_name = "POLICY")]
+    pull: Option<String>,
+
     /// Mount the container's root filesystem as read-only
     ///
     /// Converts to "ReadOnly=true"
     #[arg(long)]
     read_only: bool,
 
+    /// When running in read-only mode, mount a read-write tmpfs on /dev, /dev/shm, /run, /tmp,
+    /// and /var/tmp
+    ///
+    /// Converts to "ReadOnlyTmpfs=BOOL"
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    read_only_tmpfs: Option<bool>,
+
     /// Run an init inside the container
     ///
     /// Converts to "RunInit=true"
@@ -238,6 +354,20 @@ pub struct QuadletOptions {
     #[arg(long, value_name = "SECRET[,OPT=OPT,...]")]
     secret: Vec<String>,
 
+    /// Size of /dev/shm
+    ///
+    /// Converts to "ShmSize=NUMBER[UNIT]"
+    #[arg(long, value_name = "NUMBER[UNIT]")]
+    shm_size: Option<String>,
+
+    /// Configure namespaced kernel parameters at runtime
+    ///
+    /// Converts to "Sysctl=NAME=VALUE"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "NAME=VALUE")]
+    sysctl: Vec<String>,
+
     /// Create a tmpfs mount
     ///
     /// Converts to "Tmpfs=FS" or, if FS == /tmp, "VolatileTmp=true"
@@ -252,10 +382,26 @@ pub struct QuadletOptions {
     #[arg(long, value_name = "TIMEZONE")]
     tz: Option<String>,
 
-    /// Set the UID and, optionally, the GID used in the container
+    /// Run the container in a new user namespace using the supplied UID mapping
+    ///
+    /// Converts to "UIDMap=CONTAINER_UID:FROM_UID:AMOUNT"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "CONTAINER_UID:FROM_UID:AMOUNT")]
+    uidmap: Vec<String>,
+
+    /// Ulimit options
+    ///
+    /// Converts to "Ulimit=OPTION"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "TYPE=SOFT-LIMIT[:HARD-LIMIT]")]
+    ulimit: Vec<String>,
+
+    /// Set the user, and optionally the group, used in the container
     ///
-    /// Converts to "User=UID" and "Group=GID"
-    #[arg(short, long, value_name = "UID[:GID]")]
+    /// Converts to "User=USER[:GROUP]"
+    #[arg(short, long, value_name = "USER[:GROUP]")]
     user: Option<String>,
 
     /// Set the user namespace mode for the container
@@ -268,39 +414,47 @@ pub struct QuadletOptions {
     ///
     /// Converts to "Volume=VOLUME"
     ///
+    /// A named SOURCE-VOLUME is converted to a reference to its quadlet volume unit,
+    /// e.g. "myvol:/data" converts to "Volume=myvol.volume:/data"
+    ///
     /// Can be specified multiple times
     #[arg(
         short,
         long,
-        value_name = "[[SOURCE-VOLUME|HOST-DIR:]CONTAINER-DIR[:OPTIONS]]"
+        value_name = "[[SOURCE-VOLUME|HOST-DIR:]CONTAINER-DIR[:OPTIONS]]",
+        value_parser = clap::builder::StringValueParser::new().map(named_volume_to_unit)
     )]
     volume: Vec<String>,
+
+    /// Working directory inside the container
+    ///
+    /// Converts to "WorkingDir=DIR"
+    #[arg(short, long, value_name = "DIR")]
+    workdir: Option<PathBuf>,
 }
 
-#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(ValueEnum, Default, Debug, Clone, Copy, PartialEq, Eq)]
 enum Notify {
+    #[default]
     Conmon,
     Container,
 }
 
-impl Default for Notify {
-    fn default() -> Self {
-        Self::Conmon
+impl QuadletOptions {
+    /// Returns an error if options conflict based on their values
+    pub fn validate(&self) -> eyre::Result<()> {
+        if !self.publish.is_empty() && self.network.iter().any(|network| network == "host") {
+            eyre::bail!(
+                "`--network host` conflicts with `--publish`, \
+                    ports cannot be published when using the host's network"
+            );
+        }
+        Ok(())
     }
 }
 
 impl From<QuadletOptions> for crate::quadlet::Container {
     fn from(value: QuadletOptions) -> Self {
-        let (user, group) = if let Some(user) = value.user {
-            if let Some((uid, gid)) = user.split_once(':') {
-                (Some(String::from(uid)), Some(String::from(gid)))
-            } else {
-                (Some(user), None)
-            }
-        } else {
-            (None, None)
-        };
-
         let mut tmpfs = value.tmpfs;
         let mut volatile_tmp = false;
         tmpfs.retain(|tmpfs| {
@@ -315,14 +469,20 @@ impl From<QuadletOptions> for crate::quadlet::Container {
         Self {
             add_capability: value.cap_add,
             add_device: value.device,
+            add_host: value.add_host,
             annotation: value.annotation,
             container_name: value.name,
+            dns: value.dns,
+            dns_option: value.dns_option,
+            dns_search: value.dns_search,
             drop_capability: value.cap_drop,
+            entrypoint: value.entrypoint,
             environment: value.env,
             environment_file: value.env_file,
             environment_host: value.env_host,
             expose_host_port: value.expose,
-            group,
+            gid_map: value.gidmap,
+            group_add: value.group_add,
             health_cmd: value.health_cmd,
             health_interval: value.health_interval,
             health_on_failure: value.health_on_failure,
@@ -334,10 +494,13 @@ impl From<QuadletOptions> for crate::quadlet::Container {
             health_startup_success: value.health_startup_success,
             health_startup_timeout: value.health_startup_timeout,
             health_timeout: value.health_timeout,
+            hostname: value.hostname,
             ip: value.ip,
             ip6: value.ip6,
             label: value.label,
             log_driver: value.log_driver,
+            log_opt: value.log_opt,
+            mac_address: value.mac_address,
             mount: value.mount,
             network: value.network,
             rootfs: value.rootfs,
@@ -345,16 +508,28 @@ impl From<QuadletOptions> for crate::quadlet::Container {
                 Notify::Conmon => false,
                 Notify::Container => true,
             },
+            pids_limit: value.pids_limit,
+            pod: value.pod.map(|pod| {
+                let pod = pod.strip_prefix("new:").unwrap_or(&pod);
+                format!("{pod}.pod")
+            }),
             publish_port: value.publish,
+            pull: value.pull,
             read_only: value.read_only,
+            read_only_tmpfs: value.read_only_tmpfs,
             run_init: value.init,
             secret: value.secret,
+            shm_size: value.shm_size,
+            sysctl: value.sysctl,
             tmpfs,
             timezone: value.tz,
-            user,
+            uid_map: value.uidmap,
+            ulimit: value.ulimit,
+            user: value.user,
             user_ns: value.userns,
             volatile_tmp,
             volume: value.volume,
+            working_dir: value.workdir,
             ..Self::default()
         }
     }
@@ -371,6 +546,7 @@ impl TryFrom<ComposeService> for QuadletOptions {
 impl TryFrom<&mut ComposeService> for QuadletOptions {
     type Error = color_eyre::Report;
 
+    #[allow(clippy::too_many_lines)]
     fn try_from(value: &mut ComposeService) -> Result<Self, Self::Error> {
         let service = &mut value.service;
 
@@ -391,13 +567,15 @@ impl TryFrom<&mut ComposeService> for QuadletOptions {
 
         let env = match mem::take(&mut service.environment) {
             docker_compose_types::Environment::List(list) => list,
-            docker_compose_types::Environment::KvPair(map) => map
-                .into_iter()
-                .map(|(key, value)| {
-                    let value = value.as_ref().map(ToString::to_string).unwrap_or_default();
-                    format!("{key}={value}")
-                })
-                .collect(),
+            docker_compose_types::Environment::KvPair(mut map) => {
+                map.sort_keys();
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let value = value.as_ref().map(ToString::to_string).unwrap_or_default();
+                        format!("{key}={value}")
+                    })
+                    .collect()
+            }
         };
 
         let network = service
@@ -415,10 +593,12 @@ impl TryFrom<&mut ComposeService> for QuadletOptions {
 
         let label = match mem::take(&mut service.labels) {
             docker_compose_types::Labels::List(vec) => vec,
-            docker_compose_types::Labels::Map(map) => map
-                .into_iter()
-                .map(|(key, value)| format!("{key}={value}"))
-                .collect(),
+            docker_compose_types::Labels::Map(mut map) => {
+                map.sort_keys();
+                map.into_iter()
+                    .map(|(key, value)| format!("{key}={value}"))
+                    .collect()
+            }
         };
 
         let mut tmpfs = service
@@ -430,6 +610,50 @@ impl TryFrom<&mut ComposeService> for QuadletOptions {
             })
             .unwrap_or_default();
 
+        let sysctl = match mem::take(&mut service.sysctls) {
+            docker_compose_types::SysCtls::List(vec) => vec,
+            docker_compose_types::SysCtls::Map(mut map) => {
+                map.sort_keys();
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let value = value
+                            .as_ref()
+                            .map_or_else(|| String::from("null"), ToString::to_string);
+                        format!("{key}={value}")
+                    })
+                    .collect()
+            }
+        };
+
+        let ulimit = mem::take(&mut service.ulimits)
+            .0
+            .into_iter()
+            .map(|(kind, ulimit)| match ulimit {
+                docker_compose_types::Ulimit::Single(soft) => format!("{kind}={soft}"),
+                docker_compose_types::Ulimit::SoftHard { soft, hard } => {
+                    if hard == 0 {
+                        format!("{kind}={soft}")
+                    } else {
+                        format!("{kind}={soft}:{hard}")
+                    }
+                }
+            })
+            .collect();
+
+        let (log_driver, log_opt) = service
+            .logging
+            .take()
+            .map(|logging| {
+                let log_opt = logging
+                    .options
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(key, value)| format!("{key}={value}"))
+                    .collect();
+                (logging.driver, log_opt)
+            })
+            .unzip();
+
         let mut mount = Vec::new();
 
         let volume =
@@ -437,6 +661,14 @@ impl TryFrom<&mut ComposeService> for QuadletOptions {
 
         let service = &mut value.service;
 
+        let entrypoint = service
+            .entrypoint
+            .take()
+            .map(|entrypoint| match entrypoint {
+                docker_compose_types::Entrypoint::Simple(entrypoint) => entrypoint,
+                docker_compose_types::Entrypoint::List(list) => format!("{list:?}"),
+            });
+
         let env_file = service
             .env_file
             .take()
@@ -451,32 +683,39 @@ impl TryFrom<&mut ComposeService> for QuadletOptions {
         Ok(Self {
             cap_add: mem::take(&mut service.cap_add),
             name: service.container_name.take(),
+            dns: mem::take(&mut service.dns),
             publish,
+            entrypoint,
             env,
             env_file,
             network,
             device: mem::take(&mut service.devices),
+            add_host: mem::take(&mut service.extra_hosts),
+            shm_size: service.shm_size.take(),
             label,
+            sysctl,
             health_cmd,
             health_interval,
             health_retries,
             health_start_period,
             health_timeout,
+            hostname: service.hostname.take(),
             tmpfs,
+            ulimit,
             mount,
             user: service.user.take(),
             expose: mem::take(&mut service.expose),
-            log_driver: service
-                .logging
-                .as_mut()
-                .map(|logging| mem::take(&mut logging.driver)),
+            log_driver,
+            log_opt: log_opt.unwrap_or_default(),
             init: service.init,
             volume,
+            workdir: service.working_dir.take().map(Into::into),
             ..Self::default()
         })
     }
 }
 
+#[allow(clippy::struct_field_names)]
 #[derive(Debug, Default, Clone, PartialEq)]
 struct Healthcheck {
     health_cmd: Option<String>,
@@ -506,7 +745,7 @@ impl From<docker_compose_types::Healthcheck> for Healthcheck {
                         None
                     }
                     Some("CMD") => Some(format!("{:?}", &test[1..])),
-                    Some("CMD-SHELL") => Some(shlex::join(test[1..].iter().map(String::as_str))),
+                    Some("CMD-SHELL") => Some(test[1..].join(" ")),
                     _ => None,
                 }
             }
@@ -546,25 +785,57 @@ fn ports_try_into_publish(ports: docker_compose_types::Ports) -> color_eyre::Res
                     }
                 }
 
-                let host_ip = host_ip.map(|host_ip| host_ip + ":").unwrap_or_default();
+                let host_port = published.map(|port| match port {
+                    docker_compose_types::PublishedPort::Single(port) => port.to_string(),
+                    docker_compose_types::PublishedPort::Range(range) => range,
+                });
 
-                let host_port = published
-                        .map(|port| match port {
-                            docker_compose_types::PublishedPort::Single(port) => port.to_string(),
-                            docker_compose_types::PublishedPort::Range(range) => range,
-                        } + ":")
-                        .unwrap_or_default();
+                // An empty host port is required between the host IP and the target
+                let host = match (host_ip, host_port) {
+                    (Some(host_ip), host_port) => {
+                        format!("{host_ip}:{}:", host_port.unwrap_or_default())
+                    }
+                    (None, Some(host_port)) => host_port + ":",
+                    (None, None) => String::new(),
+                };
 
                 let protocol = protocol
                     .map(|protocol| format!("/{protocol}"))
                     .unwrap_or_default();
 
-                Ok(format!("{host_ip}{host_port}{target}{protocol}"))
+                Ok(format!("{host}{target}{protocol}"))
             })
             .collect(),
     }
 }
 
+/// Converts the source of a named volume to a reference to its quadlet `.volume` unit
+///
+/// Only sources which are valid podman volume names, mounted at an absolute path, are converted.
+/// Host paths, including relative paths and ones with systemd specifiers (e.g. `%h/data`),
+/// anonymous volumes, and sources which already reference a unit are returned unchanged.
+fn named_volume_to_unit(volume: String) -> String {
+    match volume.split_once(':') {
+        Some((source, target))
+            if is_volume_name(source)
+                && !source.ends_with(".volume")
+                && target.starts_with('/') =>
+        {
+            format!("{source}.volume:{target}")
+        }
+        _ => volume,
+    }
+}
+
+/// Returns `true` if `name` is a valid podman volume name, i.e. `[a-zA-Z0-9][a-zA-Z0-9_.-]*`
+fn is_volume_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|char| char.is_ascii_alphanumeric())
+        && chars.all(|char| char.is_ascii_alphanumeric() || matches!(char, '_' | '.' | '-'))
+}
+
 fn volumes_try_into_short(
     service: &mut ComposeService,
     tmpfs: &mut Vec<String>,
@@ -575,11 +846,12 @@ fn volumes_try_into_short(
         docker_compose_types::Volumes::Simple(volumes) => Ok(volumes
             .into_iter()
             .map(|volume| match volume.split_once(':') {
-                Some((source, target))
-                    if !source.starts_with(['.', '/', '~']) // not bind mount or anonymous volume
-                        && service.volume_has_options(source) =>
+                Some((source, target)) if !source.starts_with(['.', '/', '~']) =>
+                // not bind mount or anonymous volume
                 {
-                    format!("{source}.volume:{target}")
+                    service
+                        .volume_source(source)
+                        .map_or_else(|| volume.clone(), |source| format!("{source}:{target}"))
                 }
                 _ => volume,
             })
@@ -602,8 +874,10 @@ fn volumes_try_into_short(
                         let Some(mut source) = source else {
                             return Some(Err(eyre::eyre!("{kind} mount without a source")));
                         };
-                        if kind == "volume" && service.volume_has_options(&source) {
-                            source += ".volume";
+                        if kind == "volume" {
+                            if let Some(volume_source) = service.volume_source(&source) {
+                                source = String::from(volume_source);
+                            }
                         }
                         source += ":";
 
@@ -624,7 +898,7 @@ fn volumes_try_into_short(
                     }
                     "bind" => {
                         let Some(source) = source else {
-                            return Some(Err(eyre::eyre!("bind mount without a source")))
+                            return Some(Err(eyre::eyre!("bind mount without a source")));
                         };
                         let read_only = if read_only { ",ro" } else { "" };
                         let propagation = bind
@@ -0,0 +1,186 @@
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use super::image_to_name;
+
+#[allow(clippy::doc_markdown)]
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Image {
+    /// Generate a podman quadlet `.image` file
+    ///
+    /// Only options supported by quadlet are present
+    ///
+    /// For details on options see:
+    /// https://docs.podman.io/en/latest/markdown/podman-pull.1.html and
+    /// https://docs.podman.io/en/latest/markdown/podman-systemd.unit.5.html#image-units-image
+    #[group(skip)]
+    Pull {
+        #[command(flatten)]
+        pull: Pull,
+    },
+}
+
+impl From<Image> for crate::quadlet::Image {
+    fn from(value: Image) -> Self {
+        let Image::Pull { pull } = value;
+        pull.into()
+    }
+}
+
+impl From<Image> for crate::quadlet::Resource {
+    fn from(value: Image) -> Self {
+        crate::quadlet::Image::from(value).into()
+    }
+}
+
+impl Image {
+    pub fn name(&self) -> &str {
+        let Self::Pull { pull } = self;
+        pull.name()
+    }
+}
+
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct Pull {
+    /// All tagged images in the repository are pulled
+    ///
+    /// Converts to "AllTags=true"
+    #[arg(short, long)]
+    all_tags: bool,
+
+    /// Override the architecture of the image to be pulled
+    ///
+    /// Converts to "Arch=ARCH"
+    #[arg(long)]
+    arch: Option<String>,
+
+    /// Path of the authentication file
+    ///
+    /// Converts to "AuthFile=PATH"
+    #[arg(long, value_name = "PATH")]
+    authfile: Option<PathBuf>,
+
+    /// Use certificates at path (*.crt, *.cert, *.key) to connect to the registry
+    ///
+    /// Converts to "CertDir=PATH"
+    #[arg(long, value_name = "PATH")]
+    cert_dir: Option<PathBuf>,
+
+    /// The username and password to use to authenticate with the registry, if required
+    ///
+    /// Converts to "Creds=[USERNAME][:PASSWORD]"
+    #[arg(long, value_name = "[USERNAME][:PASSWORD]")]
+    creds: Option<String>,
+
+    /// The key and optional passphrase to be used for decryption of images
+    ///
+    /// Converts to "DecryptionKey=KEY[:PASSPHRASE]"
+    #[arg(long, value_name = "KEY[:PASSPHRASE]")]
+    decryption_key: Option<String>,
+
+    /// Override the OS of the image to be pulled
+    ///
+    /// Converts to "OS=OS"
+    #[arg(long)]
+    os: Option<String>,
+
+    /// Require HTTPS and verify certificates when contacting registries
+    ///
+    /// Converts to "TLSVerify=BOOL"
+    #[arg(long, value_name = "BOOL")]
+    tls_verify: Option<bool>,
+
+    /// Use the given variant instead of the running architecture variant for the image
+    ///
+    /// Converts to "Variant=VARIANT"
+    #[arg(long)]
+    variant: Option<String>,
+
+    /// The image to pull
+    ///
+    /// Converts to "Image=IMAGE"
+    ///
+    /// The name of the image, without the registry or tag, is used as the name of the generated
+    /// file when used with the --file option without a filename
+    image: String,
+}
+
+impl From<Pull> for crate::quadlet::Image {
+    fn from(value: Pull) -> Self {
+        Self {
+            all_tags: value.all_tags,
+            arch: value.arch,
+            auth_file: value.authfile,
+            cert_dir: value.cert_dir,
+            creds: value.creds,
+            decryption_key: value.decryption_key,
+            image: value.image,
+            os: value.os,
+            tls_verify: value.tls_verify,
+            variant: value.variant,
+        }
+    }
+}
+
+impl From<Pull> for crate::quadlet::Resource {
+    fn from(value: Pull) -> Self {
+        crate::quadlet::Image::from(value).into()
+    }
+}
+
+impl Pull {
+    pub fn name(&self) -> &str {
+        image_to_name(&self.image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::{podlet, quadlet};
+
+    #[test]
+    fn image_with_arch() {
+        let files = podlet(&[
+            "podman",
+            "pull",
+            "docker.io/library/nginx:latest",
+            "--arch",
+            "arm64",
+        ]);
+        assert_eq!(
+            files,
+            [(
+                String::from("nginx.image"),
+                String::from(
+                    "[Image]\n\
+                    Image=docker.io/library/nginx:latest\n\
+                    Arch=arm64\n"
+                )
+            )]
+        );
+    }
+
+    #[test]
+    fn registry_options() {
+        let sut = quadlet(&[
+            "podman",
+            "pull",
+            "--creds",
+            "user:pass",
+            "--os",
+            "linux",
+            "--tls-verify",
+            "false",
+            "registry.example.com/app/web",
+        ]);
+        assert_eq!(
+            sut,
+            "[Image]\n\
+            Image=registry.example.com/app/web\n\
+            Creds=user:pass\n\
+            OS=linux\n\
+            TLSVerify=false\n"
+        );
+    }
+}
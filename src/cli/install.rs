@@ -1,44 +1,118 @@
 use clap::Args;
 
-#[derive(Args, Debug, Clone, PartialEq)]
+#[allow(clippy::struct_field_names, clippy::doc_markdown)]
+#[derive(Args, Default, Debug, Clone, PartialEq)]
 pub struct Install {
-    /// Add an [Install] section to the unit
+    /// Add an [Install] section to the unit with "WantedBy=default.target"
     ///
-    /// By default, if the --wanted-by and --required-by options are not used,
-    /// the section will have "WantedBy=default.target".
+    /// Can be combined with the --wanted-by option to add more targets
     #[arg(short, long)]
-    pub install: bool,
+    install: bool,
 
     /// Add (weak) parent dependencies to the unit
     ///
-    /// Requires the --install option
-    ///
     /// Converts to "WantedBy=WANTED_BY"
     ///
     /// Can be specified multiple times
-    #[arg(long, requires = "install")]
+    #[arg(long)]
     wanted_by: Vec<String>,
 
     /// Similar to --wanted-by, but adds stronger parent dependencies
     ///
-    /// Requires the --install option
-    ///
     /// Converts to "RequiredBy=REQUIRED_BY"
     ///
     /// Can be specified multiple times
-    #[arg(long, requires = "install")]
+    #[arg(long)]
     required_by: Vec<String>,
+
+    /// Additional names the unit is installed under
+    ///
+    /// Converts to "Alias=ALIAS"
+    ///
+    /// Can be specified multiple times
+    #[arg(long)]
+    alias: Vec<String>,
+}
+
+impl Install {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 impl From<Install> for crate::quadlet::Install {
-    fn from(value: Install) -> Self {
+    fn from(mut value: Install) -> Self {
+        let default_target = String::from("default.target");
+        if value.install && !value.wanted_by.contains(&default_target) {
+            value.wanted_by.insert(0, default_target);
+        }
+
         Self {
-            wanted_by: if value.wanted_by.is_empty() && value.required_by.is_empty() {
-                vec![String::from("default.target")]
-            } else {
-                value.wanted_by
-            },
+            wanted_by: value.wanted_by,
             required_by: value.required_by,
+            alias: value.alias,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::cli::Cli;
+
+    /// Parses `args` as install options and returns the generated [Install] section
+    fn install(args: &[&str]) -> Option<String> {
+        let Cli { install, .. } = Cli::try_parse_from(["podlet"].iter().chain(args).chain(&[
+            "podman",
+            "run",
+            "quay.io/podman/hello",
+        ]))
+        .expect("args are valid");
+        (!install.is_empty()).then(|| crate::quadlet::Install::from(install).to_string())
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(install(&[]), None);
+    }
+
+    #[test]
+    fn default_target() {
+        assert_eq!(
+            install(&["--install"]).as_deref(),
+            Some("[Install]\nWantedBy=default.target\n")
+        );
+    }
+
+    #[test]
+    fn default_target_with_wanted_by() {
+        assert_eq!(
+            install(&["--install", "--wanted-by", "multi-user.target"]).as_deref(),
+            Some("[Install]\nWantedBy=default.target multi-user.target\n")
+        );
+        assert_eq!(
+            install(&["--install", "--wanted-by", "default.target"]).as_deref(),
+            Some("[Install]\nWantedBy=default.target\n")
+        );
+    }
+
+    #[test]
+    fn wanted_required_alias() {
+        assert_eq!(
+            install(&[
+                "--wanted-by",
+                "multi-user.target",
+                "--required-by",
+                "network-online.target",
+                "--alias",
+                "web.service",
+            ])
+            .as_deref(),
+            Some(
+                "[Install]\nWantedBy=multi-user.target\nRequiredBy=network-online.target\n\
+                Alias=web.service\n"
+            )
+        );
+    }
+}
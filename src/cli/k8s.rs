@@ -169,13 +169,11 @@ fn service_check_unsupported(service: &Service) -> color_eyre::Result<()> {
         ("build", service.build_.is_none()),
         ("depends_on", service.depends_on.is_empty()),
         ("env_file", service.env_file.is_none()),
-        ("profiles", service.profiles.is_empty()),
         ("links", service.links.is_empty()),
         ("net", service.net.is_none()),
         ("stop_signal", service.stop_signal.is_none()),
         ("expose", service.expose.is_empty()),
         ("volumes_from", service.volumes_from.is_empty()),
-        ("extends", service.extends.is_empty()),
         ("scale", service.scale == 0),
         ("init", !service.init),
         ("shm_size", service.shm_size.is_none()),
@@ -220,14 +218,10 @@ fn service_check_unsupported(service: &Service) -> color_eyre::Result<()> {
 
 fn healthcheck_is_disable(healthcheck: &Healthcheck) -> bool {
     healthcheck.disable
-        || healthcheck
-            .test
-            .as_ref()
-            .map(|test| match test {
-                HealthcheckTest::Single(_) => false,
-                HealthcheckTest::Multiple(test) => test == &["NONE"],
-            })
-            .unwrap_or_default()
+        || healthcheck.test.as_ref().is_some_and(|test| match test {
+            HealthcheckTest::Single(_) => false,
+            HealthcheckTest::Multiple(test) => test == &["NONE"],
+        })
 }
 
 fn healthcheck_try_into_probe(healthcheck: Healthcheck) -> color_eyre::Result<Probe> {
@@ -6,9 +6,14 @@ use std::{
     str::FromStr,
 };
 
-use clap::{Args, Subcommand};
+use clap::{Args, Subcommand, ValueEnum};
+use color_eyre::eyre;
+use thiserror::Error;
 use url::Url;
 
+use crate::quadlet::PodmanVersion;
+
+#[allow(clippy::doc_markdown)]
 #[derive(Subcommand, Debug, Clone, PartialEq)]
 pub enum Kube {
     /// Generate a podman quadlet `.kube` file
@@ -44,17 +49,58 @@ impl Kube {
 
         play.file.name().unwrap_or("pod")
     }
+
+    /// Warns about keys which are not supported by `--podman-version`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error instead if `--strict` is set
+    pub fn check_podman_version(&self) -> eyre::Result<()> {
+        let Kube::Play { play } = self;
+        let kube = crate::quadlet::Kube::from(play.clone());
+        for (key, version) in kube.unsupported_keys() {
+            let message = format!(
+                "`{key}=` requires podman v{version} or newer, \
+                but the target is podman v{}",
+                play.podman_version
+            );
+            if play.strict {
+                eyre::bail!(message);
+            }
+            eprintln!("Warning: {message}, it will be omitted");
+        }
+        Ok(())
+    }
 }
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Args, Debug, Clone, PartialEq)]
 pub struct Play {
-    /// The path to a Kubernetes YAML file containing a configmap
+    /// Build images even if they are found in the local storage
+    ///
+    /// Converts to "PodmanArgs=--build"
+    #[arg(long)]
+    build: bool,
+
+    /// The path or URL to a Kubernetes YAML file containing a configmap
     ///
     /// Converts to "ConfigMap=PATH"
     ///
     /// Can be specified multiple times
     #[arg(long, value_name = "PATH", value_delimiter = ',')]
-    configmap: Vec<PathBuf>,
+    configmap: Vec<File>,
+
+    /// Use a path as the build context directory for each image
+    ///
+    /// Converts to "PodmanArgs=--context-dir PATH"
+    #[arg(long, value_name = "PATH")]
+    context_dir: Option<PathBuf>,
+
+    /// How the unit's exit code is set from the exit codes of the pod's containers
+    ///
+    /// Converts to "ExitCodePropagation=TYPE"
+    #[arg(long, value_name = "TYPE")]
+    exit_code_propagation: Option<ExitCodePropagation>,
 
     /// Set logging driver for the pod
     ///
@@ -70,6 +116,12 @@ pub struct Play {
     #[arg(long, visible_alias = "net", value_name = "MODE")]
     network: Vec<String>,
 
+    /// Publish all exposed ports of the pod's containers to random ports on the host
+    ///
+    /// Converts to "PodmanArgs=--publish-all"
+    #[arg(long)]
+    publish_all: bool,
+
     /// Define or override a port definition in the YAML file
     ///
     /// Converts to "PublishPort=PORT"
@@ -81,9 +133,30 @@ pub struct Play {
     /// Set the user namespace mode for the pod
     ///
     /// Converts to "UserNS=MODE"
-    #[arg(long, value_name = "MODE")]
+    ///
+    /// MODE is one of `auto[:OPTIONS]`, `host`, `keep-id[:OPTIONS]`, `nomap`, or `ns:PATH`
+    #[arg(long, value_name = "MODE", value_parser = parse_userns)]
     userns: Option<String>,
 
+    /// Set the working directory of the unit to the directory of the Kubernetes YAML file
+    ///
+    /// Converts to "SetWorkingDirectory=yaml"
+    ///
+    /// Only used if the YAML file is a relative path,
+    /// so relative paths within it are resolved from the YAML file's directory
+    #[arg(long)]
+    yaml_working_directory: bool,
+
+    /// The podman version to generate the quadlet file for
+    ///
+    /// Keys not supported by the version are omitted with a warning
+    #[arg(long, value_name = "VERSION", default_value_t)]
+    podman_version: PodmanVersion,
+
+    /// Return an error for keys not supported by `--podman-version` instead of omitting them
+    #[arg(long)]
+    strict: bool,
+
     /// The path to the Kubernetes YAML file to use
     ///
     /// Converts to "Yaml=FILE"
@@ -92,17 +165,85 @@ pub struct Play {
 
 impl From<Play> for crate::quadlet::Kube {
     fn from(value: Play) -> Self {
+        let mut podman_args = Vec::new();
+        if value.build {
+            podman_args.push(String::from("--build"));
+        }
+        if let Some(context_dir) = value.context_dir {
+            podman_args.push(String::from("--context-dir"));
+            podman_args.push(context_dir.display().to_string());
+        }
+        if value.publish_all {
+            podman_args.push(String::from("--publish-all"));
+        }
+
         Self {
-            config_map: value.configmap,
+            config_map: value.configmap.iter().map(ToString::to_string).collect(),
+            exit_code_propagation: value
+                .exit_code_propagation
+                .and_then(|propagation| propagation.to_possible_value())
+                .map(|propagation| String::from(propagation.get_name())),
             log_driver: value.log_driver,
             network: value.network,
+            podman_args: (!podman_args.is_empty())
+                .then(|| shlex::join(podman_args.iter().map(String::as_str))),
+            podman_version: value.podman_version,
             publish_port: value.publish,
+            set_working_directory: (value.yaml_working_directory && value.file.is_relative_path())
+                .then(|| String::from("yaml")),
             user_ns: value.userns,
             yaml: value.file.to_string(),
         }
     }
 }
 
+/// Possible exit code propagation types
+///
+/// From [podman kube play](https://docs.podman.io/en/latest/markdown/podman-kube-play.1.html#exit-code-propagation-type)
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitCodePropagation {
+    /// Exit non-zero if all containers failed
+    All,
+    /// Exit non-zero if any container failed
+    Any,
+    /// Exit zero and ignore failed containers
+    None,
+}
+
+/// Validates a user namespace mode for `podman kube play --userns`
+fn parse_userns(mode: &str) -> Result<String, InvalidUserNs> {
+    let invalid = || InvalidUserNs(String::from(mode));
+    let (kind, options) = mode
+        .split_once(':')
+        .map_or((mode, None), |(kind, options)| (kind, Some(options)));
+
+    let numeric_options: &[&str] = match kind {
+        "auto" => &["size"],
+        "keep-id" => &["uid", "gid"],
+        "host" | "nomap" if options.is_none() => return Ok(String::from(mode)),
+        "ns" if options.is_some_and(|path| !path.is_empty()) => return Ok(String::from(mode)),
+        _ => return Err(invalid()),
+    };
+
+    for option in options.into_iter().flat_map(|options| options.split(',')) {
+        let (key, value) = option.split_once('=').ok_or_else(invalid)?;
+        let valid = if numeric_options.contains(&key) {
+            value.parse::<u32>().is_ok()
+        } else {
+            kind == "auto" && matches!(key, "uidmapping" | "gidmapping")
+        };
+        if !valid {
+            return Err(invalid());
+        }
+    }
+
+    Ok(String::from(mode))
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("`{0}` is not a valid user namespace mode")]
+struct InvalidUserNs(String);
+
 #[derive(Debug, Clone, PartialEq)]
 enum File {
     Url(Url),
@@ -128,6 +269,11 @@ impl Display for File {
 }
 
 impl File {
+    /// Returns `true` if the kube file is a relative path
+    fn is_relative_path(&self) -> bool {
+        matches!(self, Self::Path(path) if path.is_relative())
+    }
+
     /// Return the name of the kube file (without the extension)
     fn name(&self) -> Option<&str> {
         match self {
@@ -145,6 +291,158 @@ impl File {
 mod tests {
     use super::*;
 
+    /// Parses `args` as `podman kube` arguments
+    fn parse(args: &[&str]) -> Kube {
+        use clap::Parser;
+
+        use crate::cli::{Cli, Commands, PodmanCommands};
+
+        let cli = Cli::try_parse_from(["podlet", "podman", "kube"].iter().chain(args))
+            .expect("args are valid");
+        let Commands::Podman {
+            command: PodmanCommands::Kube { kube },
+        } = cli.command
+        else {
+            panic!("args are a `podman kube` command");
+        };
+        kube
+    }
+
+    #[test]
+    fn log_driver_supported() {
+        let sut = parse(&["play", "--log-driver", "journald", "pod.yaml"]);
+        assert!(sut.check_podman_version().is_ok());
+        assert_eq!(
+            crate::quadlet::Kube::from(sut).to_string(),
+            "[Kube]\nYaml=pod.yaml\nLogDriver=journald\n"
+        );
+    }
+
+    #[test]
+    fn log_driver_suppressed() {
+        let args = [
+            "play",
+            "--podman-version",
+            "4.4",
+            "--log-driver",
+            "journald",
+            "pod.yaml",
+        ];
+        let sut = parse(&args);
+        assert!(sut.check_podman_version().is_ok());
+        assert_eq!(
+            crate::quadlet::Kube::from(sut).to_string(),
+            "[Kube]\nYaml=pod.yaml\n"
+        );
+
+        let strict = parse(&[&args[..], &["--strict"]].concat());
+        assert!(strict.check_podman_version().is_err());
+    }
+
+    #[test]
+    fn userns_modes() {
+        for mode in [
+            "auto",
+            "auto:size=65536",
+            "auto:uidmapping=0:1000:1,size=1024",
+            "keep-id",
+            "keep-id:uid=1000",
+            "keep-id:uid=1000,gid=1000",
+            "host",
+            "nomap",
+            "ns:/run/user/ns",
+        ] {
+            let sut = parse(&["play", "--userns", mode, "pod.yaml"]);
+            assert_eq!(
+                crate::quadlet::Kube::from(sut).to_string(),
+                format!("[Kube]\nYaml=pod.yaml\nUserNS={mode}\n")
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_userns() {
+        for mode in [
+            "private",
+            "auto:size=big",
+            "keep-id:size=1",
+            "host:uid=1",
+            "ns:",
+        ] {
+            assert_eq!(
+                parse_userns(mode),
+                Err(InvalidUserNs(String::from(mode))),
+                "{mode}"
+            );
+        }
+    }
+
+    #[test]
+    fn yaml_working_directory() {
+        let sut = parse(&["play", "--yaml-working-directory", "kube/pod.yaml"]);
+        assert_eq!(
+            crate::quadlet::Kube::from(sut).to_string(),
+            "[Kube]\nYaml=kube/pod.yaml\nSetWorkingDirectory=yaml\n"
+        );
+
+        for file in ["/srv/kube/pod.yaml", "https://example.com/pod.yaml"] {
+            let sut = parse(&["play", "--yaml-working-directory", file]);
+            assert_eq!(
+                crate::quadlet::Kube::from(sut).to_string(),
+                format!("[Kube]\nYaml={file}\n")
+            );
+        }
+    }
+
+    #[test]
+    fn build_context_dir() {
+        let sut = parse(&["play", "--build", "--context-dir", "/src", "pod.yaml"]);
+        assert_eq!(
+            crate::quadlet::Kube::from(sut).to_string(),
+            "[Kube]\nYaml=pod.yaml\nPodmanArgs=--build --context-dir /src\n"
+        );
+    }
+
+    #[test]
+    fn exit_code_propagation() {
+        for propagation in ["all", "any", "none"] {
+            let sut = parse(&["play", "--exit-code-propagation", propagation, "pod.yaml"]);
+            assert_eq!(
+                crate::quadlet::Kube::from(sut).to_string(),
+                format!("[Kube]\nYaml=pod.yaml\nExitCodePropagation={propagation}\n")
+            );
+        }
+
+        assert!(Kube::augment_subcommands(clap::Command::new("kube"))
+            .try_get_matches_from([
+                "kube",
+                "play",
+                "--exit-code-propagation",
+                "some",
+                "pod.yaml"
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn configmap_url_and_path() {
+        let sut = parse(&[
+            "play",
+            "--configmap",
+            "https://example.com/config.yaml,config.yaml",
+            "--publish-all",
+            "pod.yaml",
+        ]);
+        assert_eq!(
+            crate::quadlet::Kube::from(sut).to_string(),
+            "[Kube]\n\
+            Yaml=pod.yaml\n\
+            ConfigMap=https://example.com/config.yaml\n\
+            ConfigMap=config.yaml\n\
+            PodmanArgs=--publish-all\n"
+        );
+    }
+
     #[test]
     fn url_file_name() {
         let sut = File::Url(Url::parse("https://example.com/test.yaml").expect("valid url"));
@@ -3,6 +3,7 @@ use std::net::IpAddr;
 use clap::{Args, Subcommand};
 use ipnet::IpNet;
 
+#[allow(clippy::doc_markdown)]
 #[derive(Subcommand, Debug, Clone, PartialEq)]
 pub enum Network {
     /// Generate a podman quadlet `.network` file
@@ -39,6 +40,7 @@ impl Network {
     }
 }
 
+#[allow(clippy::doc_markdown)]
 #[derive(Args, Debug, Clone, PartialEq)]
 pub struct Create {
     /// Disable the DNS plugin for the network
@@ -129,8 +131,62 @@ impl From<Create> for crate::quadlet::Network {
             ip_range: value.ip_range,
             ipv6: value.ipv6,
             label: value.label,
-            options: Some(value.opt.join(",")),
+            options: (!value.opt.is_empty()).then(|| value.opt.join(",")),
             subnet: value.subnet,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::quadlet;
+
+    #[test]
+    fn bridge_with_subnet() {
+        let sut = quadlet(&[
+            "podman",
+            "network",
+            "create",
+            "--driver",
+            "bridge",
+            "--subnet",
+            "10.89.0.0/24",
+            "--gateway",
+            "10.89.0.1",
+            "--ip-range",
+            "10.89.0.128/25",
+            "--internal",
+            "--label",
+            "app=web",
+            "mynet",
+        ]);
+        assert_eq!(
+            sut,
+            "[Network]\n\
+            Driver=bridge\n\
+            Gateway=10.89.0.1\n\
+            Internal=true\n\
+            IPRange=10.89.0.128/25\n\
+            Label=app=web\n\
+            Subnet=10.89.0.0/24\n"
+        );
+    }
+
+    #[test]
+    fn options() {
+        let sut = quadlet(&[
+            "podman",
+            "network",
+            "create",
+            "--opt",
+            "mtu=1500",
+            "-o",
+            "isolate=true",
+            "mynet",
+        ]);
+        assert!(sut.contains("Options=mtu=1500,isolate=true\n"));
+
+        let sut = quadlet(&["podman", "network", "create", "mynet"]);
+        assert_eq!(sut, "[Network]\n");
+    }
+}
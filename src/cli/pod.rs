@@ -0,0 +1,154 @@
+use clap::{Args, Subcommand};
+
+#[allow(clippy::doc_markdown)]
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Pod {
+    /// Generate a podman quadlet `.pod` file
+    ///
+    /// Only options supported by quadlet are present
+    ///
+    /// For details on options see:
+    /// https://docs.podman.io/en/latest/markdown/podman-pod-create.1.html and
+    /// https://docs.podman.io/en/latest/markdown/podman-systemd.unit.5.html#pod-units-pod
+    #[group(skip)]
+    Create {
+        #[command(flatten)]
+        create: Create,
+    },
+}
+
+impl From<Pod> for crate::quadlet::Pod {
+    fn from(value: Pod) -> Self {
+        let Pod::Create { create } = value;
+        create.into()
+    }
+}
+
+impl From<Pod> for crate::quadlet::Resource {
+    fn from(value: Pod) -> Self {
+        crate::quadlet::Pod::from(value).into()
+    }
+}
+
+impl Pod {
+    pub fn name(&self) -> &str {
+        let Self::Create { create } = self;
+        &create.name
+    }
+}
+
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct Create {
+    /// Set one or more OCI labels on the pod
+    ///
+    /// Converts to "Label=KEY=VALUE"
+    ///
+    /// Can be specified multiple times
+    #[arg(short, long, value_name = "KEY=VALUE")]
+    label: Vec<String>,
+
+    /// Specify a custom network for the pod
+    ///
+    /// Converts to "Network=MODE"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, visible_alias = "net", value_name = "MODE")]
+    network: Vec<String>,
+
+    /// Publish a port or range of ports from the pod to the host
+    ///
+    /// Converts to "PublishPort=PORT"
+    ///
+    /// Can be specified multiple times
+    #[arg(
+        short,
+        long,
+        value_name = "[[IP:][HOST_PORT]:]CONTAINER_PORT[/PROTOCOL]"
+    )]
+    publish: Vec<String>,
+
+    /// Mount a volume in the pod
+    ///
+    /// Converts to "Volume=VOLUME"
+    ///
+    /// Can be specified multiple times
+    #[arg(
+        short,
+        long,
+        value_name = "[[SOURCE-VOLUME|HOST-DIR:]CONTAINER-DIR[:OPTIONS]]"
+    )]
+    volume: Vec<String>,
+
+    /// The name of the pod to create
+    ///
+    /// Converts to "PodName=NAME"
+    ///
+    /// This will be used as the name of the generated file when used with
+    /// the --file option without a filename
+    #[arg(short, long)]
+    name: String,
+}
+
+impl From<Create> for crate::quadlet::Pod {
+    fn from(value: Create) -> Self {
+        Self {
+            label: value.label,
+            network: value.network,
+            pod_name: value.name,
+            publish_port: value.publish,
+            volume: value.volume,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::quadlet;
+
+    #[test]
+    fn ports_and_network() {
+        let sut = quadlet(&[
+            "podman",
+            "pod",
+            "create",
+            "--name",
+            "web",
+            "--publish",
+            "8080:80",
+            "-p",
+            "8443:443",
+            "--network",
+            "frontend.network",
+        ]);
+        assert_eq!(
+            sut,
+            "[Pod]\n\
+            Network=frontend.network\n\
+            PodName=web\n\
+            PublishPort=8080:80\n\
+            PublishPort=8443:443\n"
+        );
+    }
+
+    #[test]
+    fn label_and_volume() {
+        let sut = quadlet(&[
+            "podman",
+            "pod",
+            "create",
+            "-n",
+            "db",
+            "--label",
+            "app=db",
+            "--volume",
+            "data.volume:/var/lib/data",
+        ]);
+        assert_eq!(
+            sut,
+            "[Pod]\n\
+            Label=app=db\n\
+            PodName=db\n\
+            Volume=data.volume:/var/lib/data\n"
+        );
+    }
+}
@@ -0,0 +1,327 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use color_eyre::eyre::{self, Context};
+
+/// `[Container]` keys which convert directly to a `podman run` option with the same value
+const OPTIONS: &[(&str, &str)] = &[
+    ("AddDevice", "--device"),
+    ("AddHost", "--add-host"),
+    ("ContainerName", "--name"),
+    ("DNS", "--dns"),
+    ("DNSOption", "--dns-option"),
+    ("DNSSearch", "--dns-search"),
+    ("Entrypoint", "--entrypoint"),
+    ("EnvironmentFile", "--env-file"),
+    ("ExposeHostPort", "--expose"),
+    ("GIDMap", "--gidmap"),
+    ("GroupAdd", "--group-add"),
+    ("HealthCmd", "--health-cmd"),
+    ("HealthInterval", "--health-interval"),
+    ("HealthOnFailure", "--health-on-failure"),
+    ("HealthRetries", "--health-retries"),
+    ("HealthStartPeriod", "--health-start-period"),
+    ("HealthStartupCmd", "--health-startup-cmd"),
+    ("HealthStartupInterval", "--health-startup-interval"),
+    ("HealthStartupRetries", "--health-startup-retries"),
+    ("HealthStartupSuccess", "--health-startup-success"),
+    ("HealthStartupTimeout", "--health-startup-timeout"),
+    ("HealthTimeout", "--health-timeout"),
+    ("HostName", "--hostname"),
+    ("IP", "--ip"),
+    ("IP6", "--ip6"),
+    ("LogDriver", "--log-driver"),
+    ("LogOpt", "--log-opt"),
+    ("MacAddress", "--mac-address"),
+    ("Mount", "--mount"),
+    ("Network", "--network"),
+    ("PidsLimit", "--pids-limit"),
+    ("PublishPort", "--publish"),
+    ("Pull", "--pull"),
+    ("Rootfs", "--rootfs"),
+    ("Secret", "--secret"),
+    ("ShmSize", "--shm-size"),
+    ("Sysctl", "--sysctl"),
+    ("Tmpfs", "--tmpfs"),
+    ("Timezone", "--tz"),
+    ("UIDMap", "--uidmap"),
+    ("Ulimit", "--ulimit"),
+    ("UserNS", "--userns"),
+    ("WorkingDir", "--workdir"),
+];
+
+/// `[Container]` keys with a space separated list of values,
+/// each of which converts to a `podman run` option
+const LIST_OPTIONS: &[(&str, &str)] = &[
+    ("AddCapability", "--cap-add"),
+    ("Annotation", "--annotation"),
+    ("DropCapability", "--cap-drop"),
+    ("Environment", "--env"),
+    ("Label", "--label"),
+];
+
+/// `[Container]` boolean keys which convert to `podman run` arguments when true
+const BOOL_OPTIONS: &[(&str, &[&str])] = &[
+    ("EnvironmentHost", &["--env-host"]),
+    ("NoNewPrivileges", &["--security-opt", "no-new-privileges"]),
+    ("Notify", &["--sdnotify", "container"]),
+    ("ReadOnly", &["--read-only"]),
+    ("RunInit", &["--init"]),
+    ("SecurityLabelDisable", &["--security-opt", "label=disable"]),
+    ("VolatileTmp", &["--tmpfs", "/tmp"]),
+];
+
+/// A `podman run` command converted from the `[Container]` section of a `.container` quadlet file
+///
+/// Other sections, like `[Unit]` and `[Service]`, have no `podman run` equivalent and are ignored.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Run {
+    /// Options for `podman run`
+    args: Vec<String>,
+    image: Option<String>,
+    command: Vec<String>,
+    user: Option<String>,
+    group: Option<String>,
+    /// Keys in the `[Container]` section which could not be converted
+    pub unknown_keys: Vec<String>,
+}
+
+impl Run {
+    /// Returns the arguments for `podman run`
+    pub fn args(&self) -> impl Iterator<Item = &str> {
+        self.args
+            .iter()
+            .chain(&self.image)
+            .chain(&self.command)
+            .map(String::as_str)
+    }
+
+    /// Converts a `[Container]` section entry into `podman run` arguments
+    fn add_entry(&mut self, key: &str, value: String) -> eyre::Result<()> {
+        if let Some((_, option)) = OPTIONS.iter().find(|(name, _)| *name == key) {
+            self.args.extend([String::from(*option), value]);
+        } else if let Some((_, option)) = LIST_OPTIONS.iter().find(|(name, _)| *name == key) {
+            for value in split(&value)? {
+                self.args.extend([String::from(*option), value]);
+            }
+        } else if let Some((_, args)) = BOOL_OPTIONS.iter().find(|(name, _)| *name == key) {
+            if parse_bool(&value)? {
+                self.args.extend(args.iter().copied().map(String::from));
+            }
+        } else {
+            match key {
+                "Image" => self.image = Some(value),
+                "Exec" => self.command = split(&value)?,
+                "PodmanArgs" => self.args.extend(split(&value)?),
+                "Pod" => {
+                    let pod = value.strip_suffix(".pod").unwrap_or(&value);
+                    self.args.extend([String::from("--pod"), String::from(pod)]);
+                }
+                "Volume" => {
+                    // reverse of podlet converting named volumes into `.volume` unit references
+                    let volume = match value.split_once(':') {
+                        Some((source, target)) => source
+                            .strip_suffix(".volume")
+                            .map_or(value.clone(), |source| format!("{source}:{target}")),
+                        None => value,
+                    };
+                    self.args.extend([String::from("--volume"), volume]);
+                }
+                "ReadOnlyTmpfs" => {
+                    let read_only_tmpfs = parse_bool(&value)?;
+                    self.args
+                        .push(format!("--read-only-tmpfs={read_only_tmpfs}"));
+                }
+                "SeccompProfile" => self.security_opt(format!("seccomp={value}")),
+                "SecurityLabelFileType" => self.security_opt(format!("label=filetype:{value}")),
+                "SecurityLabelLevel" => self.security_opt(format!("label=level:{value}")),
+                "SecurityLabelType" => self.security_opt(format!("label=type:{value}")),
+                "User" => self.user = Some(value),
+                "Group" => self.group = Some(value),
+                _ => self.unknown_keys.push(String::from(key)),
+            }
+        }
+        Ok(())
+    }
+
+    fn security_opt(&mut self, security_opt: String) {
+        self.args
+            .extend([String::from("--security-opt"), security_opt]);
+    }
+}
+
+impl FromStr for Run {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut run = Self::default();
+        let mut section = None;
+        let mut has_container_section = false;
+        for line in join_continuations(s) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                has_container_section |= name == "Container";
+                section = Some(String::from(name));
+                continue;
+            }
+            if section.as_deref() != Some("Container") {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| eyre::eyre!("invalid line: `{line}`"))?;
+            let key = key.trim();
+            run.add_entry(key, value.trim().replace("%%", "%"))
+                .wrap_err_with(|| format!("invalid value for `{key}=`"))?;
+        }
+
+        if !has_container_section {
+            eyre::bail!("file does not have a [Container] section");
+        }
+
+        match (run.user.take(), run.group.take()) {
+            (Some(user), Some(group)) => run
+                .args
+                .extend([String::from("--user"), format!("{user}:{group}")]),
+            (Some(user), None) => run.args.extend([String::from("--user"), user]),
+            (None, Some(_)) => run.unknown_keys.push(String::from("Group")),
+            (None, None) => {}
+        }
+
+        if run.image.is_none() && !run.args.iter().any(|arg| arg == "--rootfs") {
+            eyre::bail!("`Image=` or `Rootfs=` is required");
+        }
+
+        Ok(run)
+    }
+}
+
+impl Display for Run {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "podman run {}", shlex::join(self.args()))
+    }
+}
+
+/// Splits the lines of `s`, joining lines ending with a `\` to the next line
+fn join_continuations(s: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut continued: Option<String> = None;
+    for line in s.lines() {
+        let line = match continued.take() {
+            Some(mut continued) => {
+                continued.push_str(line.trim_start());
+                continued
+            }
+            None => String::from(line),
+        };
+        if let Some(line) = line.strip_suffix('\\') {
+            continued = Some(format!("{line} "));
+        } else {
+            lines.push(line);
+        }
+    }
+    lines.extend(continued);
+    lines
+}
+
+/// Splits a space separated list, respecting quotes
+fn split(value: &str) -> eyre::Result<Vec<String>> {
+    shlex::split(value).ok_or_else(|| eyre::eyre!("invalid quoting: `{value}`"))
+}
+
+/// Parses a systemd boolean
+fn parse_bool(value: &str) -> eyre::Result<bool> {
+    match value {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(eyre::eyre!("`{value}` is not a valid boolean")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Converts `quadlet` into a `podman run` command and back into a quadlet
+    fn round_trip(quadlet: &str) -> String {
+        let run: Run = quadlet.parse().expect("quadlet file is valid");
+        assert!(run.unknown_keys.is_empty());
+        let args: Vec<_> = ["podman", "run"].into_iter().chain(run.args()).collect();
+        crate::cli::tests::quadlet(&args)
+    }
+
+    #[test]
+    fn run_command() {
+        let run: Run = "
+[Unit]
+Description=Web server
+
+[Container]
+Image=docker.io/library/nginx
+ContainerName=web
+Environment=\"GREETING=hello world\" LOAD=50%%
+PublishPort=8080:80
+ReadOnly=true
+Exec=nginx -g \"daemon off;\"
+"
+        .parse()
+        .expect("quadlet file is valid");
+        assert_eq!(
+            run.to_string(),
+            "podman run --name web --env \"GREETING=hello world\" --env \"LOAD=50%\" \
+                --publish 8080:80 --read-only docker.io/library/nginx nginx -g \"daemon off;\""
+        );
+    }
+
+    #[test]
+    fn round_trip_container() {
+        let quadlet = "\
+[Container]
+Image=quay.io/podman/hello
+AddCapability=NET_ADMIN NET_RAW
+ContainerName=hello
+Environment=A=1 \"B=hello world\"
+HealthCmd=curl -f http://localhost/
+Label=app=web
+Network=bridge
+NoNewPrivileges=true
+Notify=true
+PublishPort=8080:80
+SecurityLabelDisable=true
+User=1000
+VolatileTmp=true
+Volume=data.volume:/data
+PodmanArgs=--cpus 2 --memory 1g --memory-reservation 512m \\
+    --security-opt apparmor=unconfined
+Exec=sleep infinity
+";
+        assert_eq!(round_trip(quadlet), quadlet);
+    }
+
+    #[test]
+    fn round_trip_pod() {
+        let quadlet = "[Container]\nImage=quay.io/podman/hello\nPod=app.pod\n";
+        assert_eq!(round_trip(quadlet), quadlet);
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let run: Run = "[Container]\nImage=quay.io/podman/hello\nFoo=bar\n"
+            .parse()
+            .expect("quadlet file is valid");
+        assert_eq!(run.unknown_keys, ["Foo"]);
+        assert_eq!(run.to_string(), "podman run quay.io/podman/hello");
+    }
+
+    #[test]
+    fn missing_image() {
+        assert!("[Container]\nContainerName=hello\n".parse::<Run>().is_err());
+        assert!("[Unit]\nDescription=hello\n".parse::<Run>().is_err());
+    }
+}
@@ -1,26 +1,74 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
 
 use clap::{Args, ValueEnum};
 use color_eyre::eyre;
+use serde::Serialize;
+use thiserror::Error;
 
-#[derive(Args, Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Args, Default, Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Service {
     /// Configure if and when the service should be restarted
-    #[arg(long, value_name = "POLICY")]
-    restart: Option<RestartConfig>,
+    ///
+    /// Converts to "Restart=POLICY"
+    ///
+    /// The number of retries for `on-failure`, e.g. `on-failure:5`,
+    /// converts to "StartLimitBurst=RETRIES" in the [Unit] section
+    #[arg(long, value_name = "POLICY[:RETRIES]")]
+    restart: Option<Restart>,
+
+    /// Signal to stop the container, set from the container's `--stop-signal`
+    ///
+    /// Converts to "KillSignal=SIGNAL"
+    #[arg(skip)]
+    stop_signal: Option<String>,
+
+    /// Timeout to stop the container, set from the container's `--stop-timeout`
+    ///
+    /// Converts to "TimeoutStopSec=SECONDS"
+    #[arg(skip)]
+    stop_timeout: Option<u16>,
 }
 
 impl Service {
     pub fn is_empty(&self) -> bool {
         *self == Self::default()
     }
+
+    /// The number of retries for an `on-failure` restart policy
+    pub fn restart_retries(&self) -> Option<u16> {
+        self.restart.and_then(|restart| restart.retries)
+    }
+
+    /// Sets the signal and timeout systemd uses if it has to stop the container's processes itself
+    ///
+    /// Podman still needs them in `PodmanArgs=`, quadlet's `ExecStop=podman rm`
+    /// stops the container with its own stop signal and timeout.
+    pub fn with_stop(self, stop_signal: Option<String>, stop_timeout: Option<u16>) -> Self {
+        Self {
+            stop_signal,
+            stop_timeout,
+            ..self
+        }
+    }
 }
 
 impl Display for Service {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         writeln!(f, "[Service]")?;
-        if let Some(restart) = self.restart.and_then(|restart| restart.to_possible_value()) {
-            writeln!(f, "Restart={}", restart.get_name())?;
+        if let Some(policy) = self
+            .restart
+            .and_then(|restart| restart.policy.to_possible_value())
+        {
+            writeln!(f, "Restart={}", policy.get_name())?;
+        }
+        if let Some(signal) = &self.stop_signal {
+            writeln!(f, "KillSignal={signal}")?;
+        }
+        if let Some(timeout) = self.stop_timeout {
+            writeln!(f, "TimeoutStopSec={timeout}")?;
         }
         Ok(())
     }
@@ -32,18 +80,65 @@ impl TryFrom<&docker_compose_types::Service> for Service {
     fn try_from(value: &docker_compose_types::Service) -> Result<Self, Self::Error> {
         let restart = value
             .restart
-            .as_ref()
-            .map(|s| RestartConfig::from_str(s, true))
+            .as_deref()
+            .map(str::parse)
             .transpose()
             .map_err(|error| eyre::eyre!("Service's restart value is invalid: {error}"))?;
-        Ok(Self { restart })
+
+        Ok(Self {
+            restart,
+            ..Self::default()
+        })
     }
 }
 
+/// A restart policy, with an optional number of retries for `on-failure`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct Restart {
+    policy: RestartConfig,
+    retries: Option<u16>,
+}
+
+impl FromStr for Restart {
+    type Err = ParseRestartError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (policy, retries) = s
+            .split_once(':')
+            .map_or((s, None), |(policy, retries)| (policy, Some(retries)));
+
+        let policy = RestartConfig::from_str(policy, true)
+            .map_err(|_| ParseRestartError::InvalidPolicy(String::from(policy)))?;
+
+        let retries = retries
+            .map(|retries| {
+                if policy != RestartConfig::OnFailure {
+                    return Err(ParseRestartError::RetriesNotOnFailure(String::from(s)));
+                }
+                retries
+                    .parse()
+                    .map_err(|_| ParseRestartError::InvalidRetries(String::from(retries)))
+            })
+            .transpose()?;
+
+        Ok(Self { policy, retries })
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseRestartError {
+    #[error("`{0}` is not a valid restart policy")]
+    InvalidPolicy(String),
+    #[error("`{0}` is not a valid number of retries")]
+    InvalidRetries(String),
+    #[error("`{0}` is invalid, retries are only supported for `on-failure`")]
+    RetriesNotOnFailure(String),
+}
+
 /// Possible service restart configurations
 ///
 /// From [systemd.service](https://www.freedesktop.org/software/systemd/man/systemd.service.html#Restart=)
-#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 enum RestartConfig {
     No,
     OnSuccess,
@@ -54,3 +149,76 @@ enum RestartConfig {
     #[value(alias = "unless-stopped")]
     Always,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_policies() {
+        for (restart, expected) in [
+            ("always", "Restart=always\n"),
+            ("unless-stopped", "Restart=always\n"),
+            ("on-failure", "Restart=on-failure\n"),
+            ("on-failure:5", "Restart=on-failure\n"),
+            ("no", "Restart=no\n"),
+        ] {
+            let sut = Service {
+                restart: Some(restart.parse().expect("valid restart policy")),
+                ..Service::default()
+            };
+            assert_eq!(sut.to_string(), format!("[Service]\n{expected}"));
+        }
+    }
+
+    #[test]
+    fn restart_retries() {
+        let sut = Service {
+            restart: Some("on-failure:5".parse().expect("valid restart policy")),
+            ..Service::default()
+        };
+        assert_eq!(sut.restart_retries(), Some(5));
+
+        let sut = Service {
+            restart: Some("on-failure".parse().expect("valid restart policy")),
+            ..Service::default()
+        };
+        assert_eq!(sut.restart_retries(), None);
+    }
+
+    #[test]
+    fn invalid_restart() {
+        assert_eq!(
+            "sometimes".parse::<Restart>(),
+            Err(ParseRestartError::InvalidPolicy(String::from("sometimes")))
+        );
+        assert_eq!(
+            "always:5".parse::<Restart>(),
+            Err(ParseRestartError::RetriesNotOnFailure(String::from(
+                "always:5"
+            )))
+        );
+    }
+
+    #[test]
+    fn stop_signal_and_timeout() {
+        let file = crate::cli::tests::quadlet(&[
+            "podman",
+            "run",
+            "--stop-signal",
+            "SIGQUIT",
+            "--stop-timeout",
+            "30",
+            "quay.io/podman/hello",
+        ]);
+
+        assert!(
+            file.contains("PodmanArgs=--stop-signal SIGQUIT --stop-timeout 30\n"),
+            "podman stops the container with its own signal and timeout: {file}"
+        );
+        assert!(
+            file.ends_with("[Service]\nKillSignal=SIGQUIT\nTimeoutStopSec=30\n"),
+            "systemd uses the same signal and timeout: {file}"
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use std::fmt::{self, Display, Formatter};
 
 use clap::Args;
+use serde::Serialize;
+
+use crate::quadlet::escape_systemd_value;
 
 // Common systemd unit options
 // From [systemd.unit](https://www.freedesktop.org/software/systemd/man/systemd.unit.html)
-#[derive(Args, Default, Debug, Clone, PartialEq)]
+#[derive(Args, Default, Debug, Clone, PartialEq, Serialize)]
 pub struct Unit {
     /// Add a description to the unit
     ///
@@ -45,6 +48,20 @@ pub struct Unit {
     /// Can be specified multiple times
     #[arg(long)]
     after: Vec<String>,
+
+    /// Add a requirement and ordering dependency on a quadlet generated container
+    ///
+    /// Adds "NAME.service" to "Requires=" and "After="
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "NAME")]
+    requires_container: Vec<String>,
+
+    /// Number of service starts allowed, set from the retries of `--restart on-failure:RETRIES`
+    ///
+    /// Converts to "StartLimitBurst=RETRIES"
+    #[arg(skip)]
+    start_limit_burst: Option<u16>,
 }
 
 impl Unit {
@@ -52,17 +69,38 @@ impl Unit {
         *self == Self::default()
     }
 
-    pub fn add_dependencies(&mut self, depends_on: docker_compose_types::DependsOnOptions) {
+    /// Sets the description of the unit to `description`, if one was not already given
+    pub fn default_description(&mut self, description: impl FnOnce() -> String) {
+        self.description.get_or_insert_with(description);
+    }
+
+    /// Limits the number of times the service can be started, for restart retries
+    pub fn set_start_limit_burst(&mut self, start_limit_burst: u16) {
+        self.start_limit_burst = Some(start_limit_burst);
+    }
+
+    /// Adds the containers of the services in `depends_on` as dependencies
+    ///
+    /// `container_name` returns the name of the generated container file for a service name.
+    pub fn add_dependencies(
+        &mut self,
+        depends_on: docker_compose_types::DependsOnOptions,
+        container_name: impl Fn(String) -> String,
+    ) {
         let depends_on = match depends_on {
             docker_compose_types::DependsOnOptions::Simple(vec) => vec,
             docker_compose_types::DependsOnOptions::Conditional(map) => map.into_keys().collect(),
         };
 
-        self.requires.extend(
-            depends_on
-                .into_iter()
-                .map(|dependency| dependency + ".service"),
-        );
+        self.requires_container
+            .extend(depends_on.into_iter().map(container_name));
+    }
+
+    /// Services generated by quadlet for the containers in `requires_container`
+    fn container_services(&self) -> impl Iterator<Item = String> + '_ {
+        self.requires_container
+            .iter()
+            .map(|container| format!("{container}.service"))
     }
 }
 
@@ -71,25 +109,112 @@ impl Display for Unit {
         writeln!(f, "[Unit]")?;
 
         if let Some(description) = &self.description {
-            writeln!(f, "Description={description}")?;
+            writeln!(f, "Description={}", escape_systemd_value(description))?;
         }
 
         if !self.wants.is_empty() {
             writeln!(f, "Wants={}", self.wants.join(" "))?;
         }
 
-        if !self.requires.is_empty() {
-            writeln!(f, "Requires={}", self.requires.join(" "))?;
+        let requires: Vec<_> = self
+            .requires
+            .iter()
+            .cloned()
+            .chain(self.container_services())
+            .collect();
+        if !requires.is_empty() {
+            writeln!(f, "Requires={}", requires.join(" "))?;
         }
 
         if !self.before.is_empty() {
             writeln!(f, "Before={}", self.before.join(" "))?;
         }
 
-        if !self.before.is_empty() {
-            writeln!(f, "After={}", self.after.join(" "))?;
+        let after: Vec<_> = self
+            .after
+            .iter()
+            .cloned()
+            .chain(self.container_services())
+            .collect();
+        if !after.is_empty() {
+            writeln!(f, "After={}", after.join(" "))?;
+        }
+
+        if let Some(start_limit_burst) = self.start_limit_burst {
+            writeln!(f, "StartLimitBurst={start_limit_burst}")?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn after_only() {
+        let sut = Unit {
+            after: vec![String::from("network-online.target")],
+            ..Unit::default()
+        };
+        let unit = sut.to_string();
+
+        assert!(unit.contains("After=network-online.target\n"));
+        assert!(!unit.contains("Before="));
+    }
+
+    #[test]
+    fn before_and_after() {
+        let sut = Unit {
+            before: vec![String::from("shutdown.target")],
+            after: vec![String::from("network-online.target")],
+            ..Unit::default()
+        };
+
+        assert_eq!(
+            sut.to_string(),
+            "[Unit]\nBefore=shutdown.target\nAfter=network-online.target\n"
+        );
+    }
+
+    #[test]
+    fn requires_container() {
+        let sut = Unit {
+            requires: vec![String::from("network-online.target")],
+            requires_container: vec![String::from("foo")],
+            ..Unit::default()
+        };
+
+        assert_eq!(
+            sut.to_string(),
+            "[Unit]\nRequires=network-online.target foo.service\nAfter=foo.service\n"
+        );
+    }
+
+    #[test]
+    fn compose_depends_on() {
+        let mut sut = Unit::default();
+        sut.add_dependencies(
+            docker_compose_types::DependsOnOptions::Simple(vec![String::from("db")]),
+            |name| name,
+        );
+
+        assert_eq!(
+            sut.to_string(),
+            "[Unit]\nRequires=db.service\nAfter=db.service\n"
+        );
+    }
+
+    #[test]
+    fn description_percent() {
+        let sut = Unit {
+            description: Some(String::from("Uses 100% of the CPU")),
+            ..Unit::default()
+        };
+        assert_eq!(
+            sut.to_string(),
+            "[Unit]\nDescription=Uses 100%% of the CPU\n"
+        );
+    }
+}
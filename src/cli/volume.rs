@@ -4,6 +4,7 @@ use clap::{Args, Subcommand};
 
 use self::opt::Opt;
 
+#[allow(clippy::doc_markdown)]
 #[derive(Subcommand, Debug, Clone, PartialEq)]
 pub enum Volume {
     /// Generate a podman quadlet `.volume` file
@@ -42,6 +43,12 @@ impl Volume {
 
 #[derive(Args, Debug, Clone, PartialEq)]
 pub struct Create {
+    /// Specify the volume driver name
+    ///
+    /// Converts to "Driver=DRIVER"
+    #[arg(short, long)]
+    driver: Option<String>,
+
     /// Set driver specific options
     ///
     /// "copy" converts to "Copy=true"
@@ -78,8 +85,65 @@ pub struct Create {
 impl From<Create> for crate::quadlet::Volume {
     fn from(value: Create) -> Self {
         Self {
+            driver: value.driver,
             label: value.label,
             ..value.opt.into()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::tests::quadlet;
+
+    #[test]
+    fn local() {
+        let sut = quadlet(&[
+            "podman",
+            "volume",
+            "create",
+            "--driver",
+            "local",
+            "--opt",
+            "copy",
+            "--opt",
+            "o=uid=1000,gid=1000,noexec",
+            "--label",
+            "app=web",
+            "data",
+        ]);
+        assert_eq!(
+            sut,
+            "[Volume]\n\
+            Copy=true\n\
+            Driver=local\n\
+            Group=1000\n\
+            Label=app=web\n\
+            Options=noexec\n\
+            User=1000\n"
+        );
+    }
+
+    #[test]
+    fn nfs() {
+        let sut = quadlet(&[
+            "podman",
+            "volume",
+            "create",
+            "--opt",
+            "type=nfs",
+            "--opt",
+            "o=addr=192.168.1.10,rw",
+            "--opt",
+            "device=:/srv/share",
+            "share",
+        ]);
+        assert_eq!(
+            sut,
+            "[Volume]\n\
+            Device=:/srv/share\n\
+            Options=addr=192.168.1.10,rw\n\
+            Type=nfs\n"
+        );
+    }
+}
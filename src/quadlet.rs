@@ -1,7 +1,10 @@
+mod build;
 mod container;
+mod image;
 mod install;
 mod kube;
 mod network;
+mod pod;
 mod volume;
 
 use std::{
@@ -9,12 +12,16 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
+use clap::ValueEnum;
+use serde::Serialize;
+
 pub use self::{
-    container::Container, install::Install, kube::Kube, network::Network, volume::Volume,
+    build::Build, container::Container, image::Image, install::Install, kube::Kube,
+    network::Network, pod::Pod, volume::Volume,
 };
 use crate::cli::{service::Service, unit::Unit};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct File {
     pub name: String,
     pub unit: Option<Unit>,
@@ -50,25 +57,38 @@ impl File {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Resource {
+    Build(Build),
     Container(Box<Container>),
+    Image(Image),
     Kube(Kube),
     Network(Network),
+    Pod(Pod),
     Volume(Volume),
 }
 
 impl Display for Resource {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
+            Self::Build(build) => write!(f, "{build}"),
             Self::Container(container) => write!(f, "{container}"),
+            Self::Image(image) => write!(f, "{image}"),
             Self::Kube(kube) => write!(f, "{kube}"),
             Self::Network(network) => write!(f, "{network}"),
+            Self::Pod(pod) => write!(f, "{pod}"),
             Self::Volume(volume) => write!(f, "{volume}"),
         }
     }
 }
 
+impl From<Build> for Resource {
+    fn from(value: Build) -> Self {
+        Self::Build(value)
+    }
+}
+
 impl From<Container> for Resource {
     fn from(value: Container) -> Self {
         Self::Container(Box::new(value))
@@ -81,6 +101,12 @@ impl From<Box<Container>> for Resource {
     }
 }
 
+impl From<Image> for Resource {
+    fn from(value: Image) -> Self {
+        Self::Image(value)
+    }
+}
+
 impl From<Kube> for Resource {
     fn from(value: Kube) -> Self {
         Self::Kube(value)
@@ -93,6 +119,12 @@ impl From<Network> for Resource {
     }
 }
 
+impl From<Pod> for Resource {
+    fn from(value: Pod) -> Self {
+        Self::Pod(value)
+    }
+}
+
 impl From<Volume> for Resource {
     fn from(value: Volume) -> Self {
         Self::Volume(value)
@@ -103,9 +135,12 @@ impl Resource {
     /// Returns the extension that should be used for the generated file
     pub fn extension(&self) -> &'static str {
         match self {
+            Self::Build { .. } => "build",
             Self::Container { .. } => "container",
+            Self::Image { .. } => "image",
             Self::Kube { .. } => "kube",
             Self::Network { .. } => "network",
+            Self::Pod { .. } => "pod",
             Self::Volume { .. } => "volume",
         }
     }
@@ -115,7 +150,10 @@ impl Resource {
     pub fn name_to_service(&self, name: &str) -> String {
         let mut service = match self {
             Self::Container { .. } | Self::Kube { .. } => String::from(name),
+            Self::Build { .. } => format!("{name}-build"),
+            Self::Image { .. } => format!("{name}-image"),
             Self::Network { .. } => format!("{name}-network"),
+            Self::Pod { .. } => format!("{name}-pod"),
             Self::Volume { .. } => format!("{name}-volume"),
         };
         service.push_str(".service");
@@ -123,16 +161,202 @@ impl Resource {
     }
 }
 
+/// Podman versions with differing quadlet support
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum PodmanVersion {
+    #[value(name = "4.4")]
+    #[serde(rename = "4.4")]
+    V4_4,
+    #[value(name = "4.5")]
+    #[serde(rename = "4.5")]
+    V4_5,
+    #[default]
+    #[value(name = "4.8")]
+    #[serde(rename = "4.8")]
+    V4_8,
+}
+
+impl Display for PodmanVersion {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let version = self
+            .to_possible_value()
+            .expect("PodmanVersion does not have skipped variants");
+        f.write_str(version.get_name())
+    }
+}
+
+/// Escapes `value` for use in a systemd unit file
+///
+/// `\` is escaped as `\\` so it does not start an escape sequence or a line continuation,
+/// `%` is escaped as `%%` so it is not interpreted as a specifier,
+/// and newlines are replaced with line continuations.
+pub fn escape_systemd_value(value: &str) -> Cow<'_, str> {
+    if value.contains('\\') {
+        escape_specifiers(&value.replace('\\', "\\\\"))
+            .into_owned()
+            .into()
+    } else {
+        escape_specifiers(value)
+    }
+}
+
+/// Escapes `%` and newlines in `value` like [`escape_systemd_value()`], but not `\`
+///
+/// For values already quoted for systemd's word splitting, e.g. with [`shlex::join()`] or
+/// [`escape_spaces_join()`], where a `\` is part of an escape sequence.
+pub fn escape_specifiers(value: &str) -> Cow<'_, str> {
+    if value.contains(['%', '\n']) {
+        value.replace('%', "%%").replace('\n', "\\\n").into()
+    } else {
+        value.into()
+    }
+}
+
+/// Width past which [`wrap_line()`] wraps a line
+const WRAP_WIDTH: usize = 80;
+
+/// Formats `key` and its space separated `value` as `Key=value`,
+/// wrapping the value with line continuations if it is longer than [`WRAP_WIDTH`]
+///
+/// Lines are only broken between words, not within quotes,
+/// and a flag (e.g. `--cpus`) is kept together with its value.
+/// systemd joins continued lines with a space, so the wrapped value splits into the same words.
+pub fn wrap_line(key: &str, value: &str) -> String {
+    let mut line = format!("{key}=");
+    if key.len() + 1 + value.len() <= WRAP_WIDTH {
+        line.push_str(value);
+        return line;
+    }
+
+    let mut width = line.len();
+    let mut first = true;
+    for group in word_groups(value) {
+        if !first && width + 1 + group.len() > WRAP_WIDTH {
+            line.push_str(" \\\n    ");
+            width = 4;
+        } else if !first {
+            line.push(' ');
+            width += 1;
+        }
+        line.push_str(group);
+        width += group.len();
+        first = false;
+    }
+    line
+}
+
+/// Splits `value` on spaces outside of quotes,
+/// joining a flag without an `=` to the word following it
+fn word_groups(value: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, char) in value.char_indices() {
+        match char {
+            _ if escaped => escaped = false,
+            '\\' if quote != Some('\'') => escaped = true,
+            '"' | '\'' if quote.is_none() => quote = Some(char),
+            _ if quote == Some(char) => quote = None,
+            ' ' if quote.is_none() => {
+                if start < index {
+                    words.push(start..index);
+                }
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < value.len() {
+        words.push(start..value.len());
+    }
+
+    let mut groups: Vec<std::ops::Range<usize>> = Vec::new();
+    for word in words {
+        match groups.last_mut() {
+            Some(group)
+                if !value[word.clone()].starts_with('-')
+                    && value[group.clone()].starts_with('-')
+                    && !value[group.clone()].contains('=') =>
+            {
+                group.end = word.end;
+            }
+            _ => groups.push(word),
+        }
+    }
+    groups.into_iter().map(|group| &value[group]).collect()
+}
+
+/// Joins `words` with spaces for a key whose value is split into words by systemd
+///
+/// Words with spaces or newlines are quoted.
+/// `\` is escaped and newlines are written as `\n`, so each word is split back out unchanged.
 fn escape_spaces_join<'a>(words: impl IntoIterator<Item = &'a String>) -> String {
     words
         .into_iter()
         .map(|word| {
-            if word.contains(' ') {
-                format!("\"{word}\"").into()
+            let escaped: Cow<_> = if word.contains(['\\', '\n']) {
+                word.replace('\\', "\\\\").replace('\n', "\\n").into()
             } else {
                 word.into()
+            };
+            if word.contains([' ', '\n']) {
+                format!("\"{escaped}\"").into()
+            } else {
+                escaped
             }
         })
         .collect::<Vec<Cow<_>>>()
         .join(" ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Joins continued lines the way systemd does
+    fn unwrap_line(line: &str) -> String {
+        line.split(" \\\n")
+            .map(str::trim_start)
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    #[test]
+    fn escape_value() {
+        assert_eq!(escape_systemd_value("plain"), "plain");
+        assert_eq!(escape_systemd_value("100%"), "100%%");
+        assert_eq!(escape_systemd_value("ends in \\"), "ends in \\\\");
+        assert_eq!(escape_systemd_value("one\ntwo"), "one\\\ntwo");
+        assert_eq!(escape_specifiers("printf \"a\\\"b\""), "printf \"a\\\"b\"");
+    }
+
+    #[test]
+    fn wrap_short_line() {
+        let value = "--cpus 2 --memory 1g";
+        assert_eq!(
+            wrap_line("PodmanArgs", value),
+            format!("PodmanArgs={value}")
+        );
+    }
+
+    #[test]
+    fn wrap_long_line() {
+        let value = "--cpus 2 --memory 1g --memory-reservation 512m --label \"key=a long value\" \
+            --pids-limit=100 --cpu-rt-runtime 950000 --security-opt label=disable";
+        let line = wrap_line("PodmanArgs", value);
+        assert_eq!(
+            line,
+            "PodmanArgs=--cpus 2 --memory 1g --memory-reservation 512m \\\n    \
+            --label \"key=a long value\" --pids-limit=100 --cpu-rt-runtime 950000 \\\n    \
+            --security-opt label=disable"
+        );
+        assert!(line.lines().all(|line| line.len() <= WRAP_WIDTH));
+
+        let unwrapped = unwrap_line(&line);
+        assert_eq!(
+            shlex::split(unwrapped.trim_start_matches("PodmanArgs=")),
+            shlex::split(value)
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
+
+use serde::Serialize;
+
+use super::{escape_spaces_join, escape_specifiers};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Build {
+    pub annotation: Vec<String>,
+    pub arch: Option<String>,
+    pub auth_file: Option<PathBuf>,
+    pub environment: Vec<String>,
+    pub file: Option<String>,
+    pub image_tag: String,
+    pub label: Vec<String>,
+    pub network: Vec<String>,
+    pub pull: Option<String>,
+    pub set_working_directory: Option<String>,
+    pub target: Option<String>,
+    pub tls_verify: Option<bool>,
+}
+
+impl Display for Build {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "[Build]")?;
+
+        writeln!(f, "ImageTag={}", self.image_tag)?;
+
+        for annotation in &self.annotation {
+            writeln!(
+                f,
+                "Annotation={}",
+                escape_specifiers(&escape_spaces_join([annotation]))
+            )?;
+        }
+
+        if let Some(arch) = &self.arch {
+            writeln!(f, "Arch={arch}")?;
+        }
+
+        if let Some(auth_file) = &self.auth_file {
+            writeln!(f, "AuthFile={}", auth_file.display())?;
+        }
+
+        for environment in &self.environment {
+            writeln!(
+                f,
+                "Environment={}",
+                escape_specifiers(&escape_spaces_join([environment]))
+            )?;
+        }
+
+        if let Some(file) = &self.file {
+            writeln!(f, "File={file}")?;
+        }
+
+        for label in &self.label {
+            writeln!(
+                f,
+                "Label={}",
+                escape_specifiers(&escape_spaces_join([label]))
+            )?;
+        }
+
+        for network in &self.network {
+            writeln!(f, "Network={network}")?;
+        }
+
+        if let Some(pull) = &self.pull {
+            writeln!(f, "Pull={pull}")?;
+        }
+
+        if let Some(directory) = &self.set_working_directory {
+            writeln!(f, "SetWorkingDirectory={directory}")?;
+        }
+
+        if let Some(target) = &self.target {
+            writeln!(f, "Target={target}")?;
+        }
+
+        if let Some(tls_verify) = self.tls_verify {
+            writeln!(f, "TLSVerify={tls_verify}")?;
+        }
+
+        Ok(())
+    }
+}
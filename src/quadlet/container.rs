@@ -4,22 +4,31 @@ use std::{
     path::PathBuf,
 };
 
-use super::escape_spaces_join;
+use serde::Serialize;
 
-#[derive(Debug, Default, Clone, PartialEq)]
-#[allow(clippy::struct_excessive_bools)]
+use super::{escape_spaces_join, escape_specifiers, escape_systemd_value, wrap_line};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[allow(clippy::struct_excessive_bools, clippy::struct_field_names)]
 pub struct Container {
     pub add_capability: Vec<String>,
     pub add_device: Vec<String>,
+    pub add_host: Vec<String>,
     pub annotation: Vec<String>,
     pub container_name: Option<String>,
+    pub dns: Vec<String>,
+    pub dns_option: Vec<String>,
+    pub dns_search: Vec<String>,
     pub drop_capability: Vec<String>,
+    pub entrypoint: Option<String>,
     pub environment: Vec<String>,
     pub environment_file: Vec<PathBuf>,
     pub environment_host: bool,
     pub exec: Option<String>,
     pub expose_host_port: Vec<String>,
+    pub gid_map: Vec<String>,
     pub group: Option<String>,
+    pub group_add: Vec<String>,
     pub health_cmd: Option<String>,
     pub health_interval: Option<String>,
     pub health_on_failure: Option<String>,
@@ -31,19 +40,26 @@ pub struct Container {
     pub health_startup_success: Option<u16>,
     pub health_startup_timeout: Option<String>,
     pub health_timeout: Option<String>,
+    pub hostname: Option<String>,
     pub image: String,
     pub ip: Option<Ipv4Addr>,
     pub ip6: Option<Ipv6Addr>,
     pub label: Vec<String>,
     pub log_driver: Option<String>,
+    pub log_opt: Vec<String>,
+    pub mac_address: Option<String>,
     pub mount: Vec<String>,
     pub network: Vec<String>,
     pub no_new_privileges: bool,
     pub rootfs: Option<String>,
     pub notify: bool,
+    pub pids_limit: Option<i64>,
+    pub pod: Option<String>,
     pub podman_args: Option<String>,
     pub publish_port: Vec<String>,
+    pub pull: Option<String>,
     pub read_only: bool,
+    pub read_only_tmpfs: Option<bool>,
     pub run_init: bool,
     pub seccomp_profile: Option<String>,
     pub security_label_disable: bool,
@@ -51,12 +67,17 @@ pub struct Container {
     pub security_label_level: Option<String>,
     pub security_label_type: Option<String>,
     pub secret: Vec<String>,
+    pub shm_size: Option<String>,
+    pub sysctl: Vec<String>,
     pub tmpfs: Vec<String>,
     pub timezone: Option<String>,
+    pub uid_map: Vec<String>,
+    pub ulimit: Vec<String>,
     pub user: Option<String>,
     pub user_ns: Option<String>,
     pub volatile_tmp: bool,
     pub volume: Vec<String>,
+    pub working_dir: Option<PathBuf>,
 }
 
 impl Display for Container {
@@ -74,20 +95,46 @@ impl Display for Container {
             writeln!(f, "AddDevice={device}")?;
         }
 
-        if !self.annotation.is_empty() {
-            writeln!(f, "Annotation={}", escape_spaces_join(&self.annotation))?;
+        for host in &self.add_host {
+            writeln!(f, "AddHost={host}")?;
+        }
+
+        for annotation in &self.annotation {
+            writeln!(
+                f,
+                "Annotation={}",
+                escape_specifiers(&escape_spaces_join([annotation]))
+            )?;
         }
 
         if let Some(name) = &self.container_name {
             writeln!(f, "ContainerName={name}")?;
         }
 
+        for dns in &self.dns {
+            writeln!(f, "DNS={dns}")?;
+        }
+
+        for option in &self.dns_option {
+            writeln!(f, "DNSOption={option}")?;
+        }
+
+        for search in &self.dns_search {
+            writeln!(f, "DNSSearch={search}")?;
+        }
+
         if !self.drop_capability.is_empty() {
             writeln!(f, "DropCapability={}", self.drop_capability.join(" "))?;
         }
 
+        if let Some(entrypoint) = &self.entrypoint {
+            writeln!(f, "Entrypoint={}", escape_systemd_value(entrypoint))?;
+        }
+
         if !self.environment.is_empty() {
-            writeln!(f, "Environment={}", escape_spaces_join(&self.environment))?;
+            let environment = escape_spaces_join(&self.environment);
+            let environment = escape_specifiers(&environment);
+            writeln!(f, "{}", wrap_line("Environment", &environment))?;
         }
 
         for file in &self.environment_file {
@@ -102,12 +149,20 @@ impl Display for Container {
             writeln!(f, "ExposeHostPort={port}")?;
         }
 
+        for gid_map in &self.gid_map {
+            writeln!(f, "GIDMap={gid_map}")?;
+        }
+
         if let Some(group) = &self.group {
             writeln!(f, "Group={group}")?;
         }
 
+        for group in &self.group_add {
+            writeln!(f, "GroupAdd={group}")?;
+        }
+
         if let Some(command) = &self.health_cmd {
-            writeln!(f, "HealthCmd={command}")?;
+            writeln!(f, "HealthCmd={}", escape_systemd_value(command))?;
         }
 
         if let Some(interval) = &self.health_interval {
@@ -127,7 +182,7 @@ impl Display for Container {
         }
 
         if let Some(command) = &self.health_startup_cmd {
-            writeln!(f, "HealthStartupCmd={command}")?;
+            writeln!(f, "HealthStartupCmd={}", escape_systemd_value(command))?;
         }
 
         if let Some(interval) = &self.health_startup_interval {
@@ -150,6 +205,10 @@ impl Display for Container {
             writeln!(f, "HealthTimeout={timeout}")?;
         }
 
+        if let Some(hostname) = &self.hostname {
+            writeln!(f, "HostName={hostname}")?;
+        }
+
         if let Some(ip) = &self.ip {
             writeln!(f, "IP={ip}")?;
         }
@@ -158,14 +217,26 @@ impl Display for Container {
             writeln!(f, "IP6={ip6}")?;
         }
 
-        if !self.label.is_empty() {
-            writeln!(f, "Label={}", escape_spaces_join(&self.label))?;
+        for label in &self.label {
+            writeln!(
+                f,
+                "Label={}",
+                escape_specifiers(&escape_spaces_join([label]))
+            )?;
         }
 
         if let Some(log_driver) = &self.log_driver {
             writeln!(f, "LogDriver={log_driver}")?;
         }
 
+        for log_opt in &self.log_opt {
+            writeln!(f, "LogOpt={log_opt}")?;
+        }
+
+        if let Some(mac_address) = &self.mac_address {
+            writeln!(f, "MacAddress={mac_address}")?;
+        }
+
         for mount in &self.mount {
             writeln!(f, "Mount={mount}")?;
         }
@@ -186,14 +257,30 @@ impl Display for Container {
             writeln!(f, "Notify=true")?;
         }
 
+        if let Some(limit) = self.pids_limit {
+            writeln!(f, "PidsLimit={limit}")?;
+        }
+
+        if let Some(pod) = &self.pod {
+            writeln!(f, "Pod={pod}")?;
+        }
+
         for port in &self.publish_port {
             writeln!(f, "PublishPort={port}")?;
         }
 
+        if let Some(pull) = &self.pull {
+            writeln!(f, "Pull={pull}")?;
+        }
+
         if self.read_only {
             writeln!(f, "ReadOnly=true")?;
         }
 
+        if let Some(read_only_tmpfs) = self.read_only_tmpfs {
+            writeln!(f, "ReadOnlyTmpfs={read_only_tmpfs}")?;
+        }
+
         if self.run_init {
             writeln!(f, "RunInit=true")?;
         }
@@ -222,6 +309,14 @@ impl Display for Container {
             writeln!(f, "Secret={secret}")?;
         }
 
+        if let Some(shm_size) = &self.shm_size {
+            writeln!(f, "ShmSize={shm_size}")?;
+        }
+
+        for sysctl in &self.sysctl {
+            writeln!(f, "Sysctl={sysctl}")?;
+        }
+
         for tmpfs in &self.tmpfs {
             writeln!(f, "Tmpfs={tmpfs}")?;
         }
@@ -230,6 +325,14 @@ impl Display for Container {
             writeln!(f, "Timezone={timezone}")?;
         }
 
+        for uid_map in &self.uid_map {
+            writeln!(f, "UIDMap={uid_map}")?;
+        }
+
+        for ulimit in &self.ulimit {
+            writeln!(f, "Ulimit={ulimit}")?;
+        }
+
         if let Some(user) = &self.user {
             writeln!(f, "User={user}")?;
         }
@@ -246,12 +349,16 @@ impl Display for Container {
             writeln!(f, "Volume={volume}")?;
         }
 
+        if let Some(working_dir) = &self.working_dir {
+            writeln!(f, "WorkingDir={}", working_dir.display())?;
+        }
+
         if let Some(podman_args) = &self.podman_args {
-            writeln!(f, "PodmanArgs={podman_args}")?;
+            writeln!(f, "{}", wrap_line("PodmanArgs", podman_args))?;
         }
 
         if let Some(exec) = &self.exec {
-            writeln!(f, "Exec={exec}")?;
+            writeln!(f, "Exec={}", escape_specifiers(exec))?;
         }
 
         Ok(())
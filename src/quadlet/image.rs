@@ -0,0 +1,67 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[allow(clippy::struct_field_names)]
+pub struct Image {
+    pub all_tags: bool,
+    pub arch: Option<String>,
+    pub auth_file: Option<PathBuf>,
+    pub cert_dir: Option<PathBuf>,
+    pub creds: Option<String>,
+    pub decryption_key: Option<String>,
+    pub image: String,
+    pub os: Option<String>,
+    pub tls_verify: Option<bool>,
+    pub variant: Option<String>,
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "[Image]")?;
+
+        writeln!(f, "Image={}", self.image)?;
+
+        if self.all_tags {
+            writeln!(f, "AllTags=true")?;
+        }
+
+        if let Some(arch) = &self.arch {
+            writeln!(f, "Arch={arch}")?;
+        }
+
+        if let Some(auth_file) = &self.auth_file {
+            writeln!(f, "AuthFile={}", auth_file.display())?;
+        }
+
+        if let Some(cert_dir) = &self.cert_dir {
+            writeln!(f, "CertDir={}", cert_dir.display())?;
+        }
+
+        if let Some(creds) = &self.creds {
+            writeln!(f, "Creds={creds}")?;
+        }
+
+        if let Some(key) = &self.decryption_key {
+            writeln!(f, "DecryptionKey={key}")?;
+        }
+
+        if let Some(os) = &self.os {
+            writeln!(f, "OS={os}")?;
+        }
+
+        if let Some(tls_verify) = self.tls_verify {
+            writeln!(f, "TLSVerify={tls_verify}")?;
+        }
+
+        if let Some(variant) = &self.variant {
+            writeln!(f, "Variant={variant}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,14 @@
 use std::fmt::{self, Display, Formatter};
 
+use serde::Serialize;
+
 use super::escape_spaces_join;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Install {
     pub wanted_by: Vec<String>,
     pub required_by: Vec<String>,
+    pub alias: Vec<String>,
 }
 
 impl Display for Install {
@@ -20,6 +23,10 @@ impl Display for Install {
             writeln!(f, "RequiredBy={}", escape_spaces_join(&self.required_by))?;
         }
 
+        if !self.alias.is_empty() {
+            writeln!(f, "Alias={}", escape_spaces_join(&self.alias))?;
+        }
+
         Ok(())
     }
 }
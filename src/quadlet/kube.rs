@@ -1,18 +1,60 @@
-use std::{
-    fmt::{self, Display, Formatter},
-    path::PathBuf,
-};
+use std::fmt::{self, Display, Formatter};
 
-#[derive(Debug, Clone, PartialEq)]
+use serde::Serialize;
+
+use super::{wrap_line, PodmanVersion};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Kube {
-    pub config_map: Vec<PathBuf>,
+    pub config_map: Vec<String>,
+    pub exit_code_propagation: Option<String>,
     pub log_driver: Option<String>,
     pub network: Vec<String>,
+    pub podman_args: Option<String>,
+    pub podman_version: PodmanVersion,
     pub publish_port: Vec<String>,
+    pub set_working_directory: Option<String>,
     pub user_ns: Option<String>,
     pub yaml: String,
 }
 
+/// `[Kube]` keys added after podman v4.4, along with the podman version that added them
+const MIN_VERSIONS: &[(&str, PodmanVersion)] = &[
+    ("ExitCodePropagation", PodmanVersion::V4_8),
+    ("LogDriver", PodmanVersion::V4_5),
+    ("PodmanArgs", PodmanVersion::V4_8),
+    ("SetWorkingDirectory", PodmanVersion::V4_8),
+];
+
+/// Returns the podman version that added `key`, if it was added after podman v4.4
+fn min_version(key: &str) -> Option<PodmanVersion> {
+    MIN_VERSIONS
+        .iter()
+        .find_map(|(name, version)| (*name == key).then_some(*version))
+}
+
+impl Kube {
+    /// Returns the keys which are set but not supported by `podman_version`,
+    /// along with the podman version that added them
+    pub fn unsupported_keys(&self) -> Vec<(&'static str, PodmanVersion)> {
+        [
+            ("ExitCodePropagation", self.exit_code_propagation.is_some()),
+            ("LogDriver", self.log_driver.is_some()),
+            ("PodmanArgs", self.podman_args.is_some()),
+            ("SetWorkingDirectory", self.set_working_directory.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, set)| *set)
+        .filter_map(|(key, _)| self.unsupported(key).map(|version| (key, version)))
+        .collect()
+    }
+
+    /// Returns the podman version that added `key` if it is newer than `podman_version`
+    fn unsupported(&self, key: &str) -> Option<PodmanVersion> {
+        min_version(key).filter(|version| self.podman_version < *version)
+    }
+}
+
 impl Display for Kube {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         writeln!(f, "[Kube]")?;
@@ -20,11 +62,19 @@ impl Display for Kube {
         writeln!(f, "Yaml={}", self.yaml)?;
 
         for config_map in &self.config_map {
-            writeln!(f, "ConfigMap={}", config_map.display())?;
+            writeln!(f, "ConfigMap={config_map}")?;
+        }
+
+        if let Some(propagation) = &self.exit_code_propagation {
+            if self.unsupported("ExitCodePropagation").is_none() {
+                writeln!(f, "ExitCodePropagation={propagation}")?;
+            }
         }
 
         if let Some(log_driver) = &self.log_driver {
-            writeln!(f, "LogDriver={log_driver}")?;
+            if self.unsupported("LogDriver").is_none() {
+                writeln!(f, "LogDriver={log_driver}")?;
+            }
         }
 
         for network in &self.network {
@@ -35,10 +85,22 @@ impl Display for Kube {
             writeln!(f, "PublishPort={port}")?;
         }
 
+        if let Some(directory) = &self.set_working_directory {
+            if self.unsupported("SetWorkingDirectory").is_none() {
+                writeln!(f, "SetWorkingDirectory={directory}")?;
+            }
+        }
+
         if let Some(user_ns) = &self.user_ns {
             writeln!(f, "UserNS={user_ns}")?;
         }
 
+        if let Some(podman_args) = &self.podman_args {
+            if self.unsupported("PodmanArgs").is_none() {
+                writeln!(f, "{}", wrap_line("PodmanArgs", podman_args))?;
+            }
+        }
+
         Ok(())
     }
 }
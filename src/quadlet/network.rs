@@ -5,10 +5,11 @@ use std::{
 
 use color_eyre::eyre::{self, Context};
 use ipnet::IpNet;
+use serde::Serialize;
 
-use super::escape_spaces_join;
+use super::{escape_spaces_join, escape_specifiers};
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct Network {
     pub disable_dns: bool,
     pub driver: Option<String>,
@@ -122,7 +123,11 @@ impl Display for Network {
         }
 
         if !self.label.is_empty() {
-            writeln!(f, "Label={}", escape_spaces_join(&self.label))?;
+            writeln!(
+                f,
+                "Label={}",
+                escape_specifiers(&escape_spaces_join(&self.label))
+            )?;
         }
 
         if let Some(options) = &self.options {
@@ -0,0 +1,45 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::Serialize;
+
+use super::{escape_spaces_join, escape_specifiers};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[allow(clippy::struct_field_names)]
+pub struct Pod {
+    pub label: Vec<String>,
+    pub network: Vec<String>,
+    pub pod_name: String,
+    pub publish_port: Vec<String>,
+    pub volume: Vec<String>,
+}
+
+impl Display for Pod {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "[Pod]")?;
+
+        for label in &self.label {
+            writeln!(
+                f,
+                "Label={}",
+                escape_specifiers(&escape_spaces_join([label]))
+            )?;
+        }
+
+        for network in &self.network {
+            writeln!(f, "Network={network}")?;
+        }
+
+        writeln!(f, "PodName={}", self.pod_name)?;
+
+        for port in &self.publish_port {
+            writeln!(f, "PublishPort={port}")?;
+        }
+
+        for volume in &self.volume {
+            writeln!(f, "Volume={volume}")?;
+        }
+
+        Ok(())
+    }
+}
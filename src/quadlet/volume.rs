@@ -1,15 +1,17 @@
 use std::fmt::{self, Display, Formatter};
 
 use color_eyre::eyre::{self, Context};
+use serde::Serialize;
 
 use crate::cli::volume::opt::Opt;
 
-use super::escape_spaces_join;
+use super::{escape_spaces_join, escape_specifiers};
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct Volume {
     pub copy: bool,
     pub device: Option<String>,
+    pub driver: Option<String>,
     pub group: Option<String>,
     pub label: Vec<String>,
     pub options: Option<String>,
@@ -22,8 +24,13 @@ impl TryFrom<docker_compose_types::ComposeVolume> for Volume {
 
     fn try_from(value: docker_compose_types::ComposeVolume) -> Result<Self, Self::Error> {
         let unsupported_options = [
-            ("driver", value.driver.is_none()),
-            ("external", value.external.is_none()),
+            (
+                "external",
+                matches!(
+                    value.external,
+                    None | Some(docker_compose_types::ExternalVolume::Bool(false))
+                ),
+            ),
             ("name", value.name.is_none()),
         ];
         for (option, not_present) in unsupported_options {
@@ -55,6 +62,7 @@ impl TryFrom<docker_compose_types::ComposeVolume> for Volume {
         };
 
         Ok(Self {
+            driver: value.driver,
             label,
             ..options.into()
         })
@@ -73,12 +81,20 @@ impl Display for Volume {
             writeln!(f, "Device={device}")?;
         }
 
+        if let Some(driver) = &self.driver {
+            writeln!(f, "Driver={driver}")?;
+        }
+
         if let Some(group) = &self.group {
             writeln!(f, "Group={group}")?;
         }
 
         if !self.label.is_empty() {
-            writeln!(f, "Label={}", escape_spaces_join(&self.label))?;
+            writeln!(
+                f,
+                "Label={}",
+                escape_specifiers(&escape_spaces_join(&self.label))
+            )?;
         }
 
         if let Some(options) = &self.options {